@@ -0,0 +1,460 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+#[cfg(test)]
+mod test;
+
+/// Number of history entries kept in memory.
+const HISTORY_SIZE: usize = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    Up,
+    Down,
+    Tab,
+    /// Ctrl-R
+    Search,
+    /// Ctrl-K
+    KillEnd,
+    /// Ctrl-U
+    KillStart,
+    /// Ctrl-C
+    Interrupt,
+    /// Ctrl-D
+    Eof,
+    Escape,
+    Unknown,
+}
+
+fn read_byte(r: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut b = [0];
+    match r.read(&mut b)? {
+        0 => Ok(None),
+        _ => Ok(Some(b[0])),
+    }
+}
+
+/// Read one key press from a terminal in raw mode, or `None` at the end of
+/// input.
+pub fn read_key(r: &mut impl Read) -> io::Result<Option<Key>> {
+    let b = match read_byte(r)? {
+        Some(b) => b,
+        None => return Ok(None),
+    };
+    let key = match b {
+        b'\r' | b'\n' => Key::Enter,
+        1 => Key::Home,
+        2 => Key::Left,
+        3 => Key::Interrupt,
+        4 => Key::Eof,
+        5 => Key::End,
+        6 => Key::Right,
+        b'\t' => Key::Tab,
+        11 => Key::KillEnd,
+        14 => Key::Down,
+        16 => Key::Up,
+        18 => Key::Search,
+        21 => Key::KillStart,
+        8 | 127 => Key::Backspace,
+        27 => read_escape(r)?,
+        b if b < 32 => Key::Unknown,
+        b => read_utf8(b, r)?,
+    };
+    Ok(Some(key))
+}
+
+/// Decode the rest of an escape sequence such as `ESC [ A`.
+fn read_escape(r: &mut impl Read) -> io::Result<Key> {
+    let key = match read_byte(r)? {
+        Some(b'[') | Some(b'O') => match read_byte(r)? {
+            Some(b'A') => Key::Up,
+            Some(b'B') => Key::Down,
+            Some(b'C') => Key::Right,
+            Some(b'D') => Key::Left,
+            Some(b'H') => Key::Home,
+            Some(b'F') => Key::End,
+            Some(b) if b.is_ascii_digit() => {
+                let mut n = vec![b];
+                loop {
+                    match read_byte(r)? {
+                        Some(b'~') => break,
+                        Some(b) if b.is_ascii_digit() || b == b';' => n.push(b),
+                        _ => return Ok(Key::Unknown),
+                    }
+                }
+                match n.as_slice() {
+                    b"1" | b"7" => Key::Home,
+                    b"3" => Key::Delete,
+                    b"4" | b"8" => Key::End,
+                    _ => Key::Unknown,
+                }
+            }
+            _ => Key::Unknown,
+        },
+        _ => Key::Escape,
+    };
+    Ok(key)
+}
+
+fn read_utf8(first: u8, r: &mut impl Read) -> io::Result<Key> {
+    let len = match first {
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => 1,
+    };
+    let mut bytes = vec![first];
+    for _ in 1..len {
+        match read_byte(r)? {
+            Some(b) => bytes.push(b),
+            None => return Ok(Key::Unknown),
+        }
+    }
+    Ok(match std::str::from_utf8(&bytes) {
+        Ok(s) => s.chars().next().map_or(Key::Unknown, Key::Char),
+        Err(_) => Key::Unknown,
+    })
+}
+
+/// The result of a key press.
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    Continue,
+    /// Show the candidates of an ambiguous completion.
+    List(Vec<String>),
+    Done(String),
+    Eof,
+}
+
+/// A reverse search through the history.
+struct Search {
+    query: String,
+    /// Index of the matching history entry.
+    found: Option<usize>,
+}
+
+/// The line being edited.
+pub struct Line<'a> {
+    buf: Vec<char>,
+    pos: usize,
+    history: &'a [String],
+    /// Index of the history entry shown, `history.len()` for the new line.
+    index: usize,
+    /// The new line while browsing the history.
+    saved: Vec<char>,
+    search: Option<Search>,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(history: &'a [String]) -> Line<'a> {
+        Line {
+            buf: Vec::new(),
+            pos: 0,
+            history,
+            index: history.len(),
+            saved: Vec::new(),
+            search: None,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.buf.iter().collect()
+    }
+
+    /// Update the line for `key` completing words from `words`.
+    pub fn handle(&mut self, key: Key, words: &[String]) -> Action {
+        if self.search.is_some() {
+            match self.handle_search(key) {
+                Some(action) => return action,
+                None => self.end_search(),
+            }
+        }
+        match key {
+            Key::Char(c) => {
+                self.buf.insert(self.pos, c);
+                self.pos += 1;
+            }
+            Key::Enter => return Action::Done(self.text()),
+            Key::Backspace if self.pos > 0 => {
+                self.pos -= 1;
+                self.buf.remove(self.pos);
+            }
+            Key::Delete if self.pos < self.buf.len() => {
+                self.buf.remove(self.pos);
+            }
+            Key::Eof if self.buf.is_empty() => return Action::Eof,
+            Key::Eof if self.pos < self.buf.len() => {
+                self.buf.remove(self.pos);
+            }
+            Key::Left if self.pos > 0 => self.pos -= 1,
+            Key::Right if self.pos < self.buf.len() => self.pos += 1,
+            Key::Home => self.pos = 0,
+            Key::End => self.pos = self.buf.len(),
+            Key::Up if self.index > 0 => self.show(self.index - 1),
+            Key::Down if self.index < self.history.len() => {
+                self.show(self.index + 1)
+            }
+            Key::KillEnd => self.buf.truncate(self.pos),
+            Key::KillStart => {
+                self.buf.drain(..self.pos);
+                self.pos = 0;
+            }
+            Key::Interrupt => return Action::Done(String::new()),
+            Key::Search => {
+                self.search = Some(Search {
+                    query: String::new(),
+                    found: None,
+                })
+            }
+            Key::Tab => return self.complete(words),
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    /// Show the history entry `i`, or the new line after the last entry.
+    fn show(&mut self, i: usize) {
+        if self.index == self.history.len() {
+            self.saved = self.buf.clone();
+        }
+        self.index = i;
+        self.buf = match self.history.get(i) {
+            Some(s) => s.chars().collect(),
+            None => self.saved.clone(),
+        };
+        self.pos = self.buf.len();
+    }
+
+    /// Handle `key` in a reverse search, or return `None` to leave the
+    /// search and handle the key as usual.
+    fn handle_search(&mut self, key: Key) -> Option<Action> {
+        let mut search = self.search.take()?;
+        let end = search.found.unwrap_or(self.history.len());
+        match key {
+            Key::Char(c) => {
+                search.query.push(c);
+                search.found = find(self.history, &search.query, end + 1);
+            }
+            Key::Backspace => {
+                search.query.pop();
+                let len = self.history.len();
+                search.found = find(self.history, &search.query, len);
+            }
+            Key::Search => {
+                search.found =
+                    find(self.history, &search.query, end).or(search.found);
+            }
+            Key::Interrupt | Key::Escape => return Some(Action::Continue),
+            _ => {
+                self.search = Some(search);
+                return None;
+            }
+        }
+        self.search = Some(search);
+        Some(Action::Continue)
+    }
+
+    /// Leave the search with the matching entry as the line.
+    fn end_search(&mut self) {
+        if let Some(Search { found: Some(i), .. }) = self.search.take() {
+            self.buf = self.history[i].chars().collect();
+            self.pos = self.buf.len();
+            self.index = self.history.len();
+        }
+    }
+
+    /// Complete the word before the cursor.
+    fn complete(&mut self, words: &[String]) -> Action {
+        let start = self.buf[..self.pos]
+            .iter()
+            .rposition(|&c| !(c.is_alphanumeric() || c == '_'))
+            .map_or(0, |i| match self.buf[i] {
+                '\\' => i,
+                _ => i + 1,
+            });
+        let prefix: String = self.buf[start..self.pos].iter().collect();
+        if prefix.is_empty() {
+            return Action::Continue;
+        }
+        let matches: Vec<&String> =
+            words.iter().filter(|w| w.starts_with(&prefix)).collect();
+        let common = match matches.split_first() {
+            None => return Action::Continue,
+            Some((first, rest)) => rest.iter().fold(first.as_str(), |c, w| {
+                let n = c
+                    .char_indices()
+                    .zip(w.chars())
+                    .find(|&((_, a), b)| a != b)
+                    .map_or(c.len().min(w.len()), |((i, _), _)| i);
+                &c[..n]
+            }),
+        };
+        if common.len() > prefix.len() {
+            for c in common[prefix.len()..].chars() {
+                self.buf.insert(self.pos, c);
+                self.pos += 1;
+            }
+            Action::Continue
+        } else if matches.len() > 1 {
+            Action::List(matches.into_iter().cloned().collect())
+        } else {
+            Action::Continue
+        }
+    }
+
+    /// Return the escape sequences which redraw the line after `prompt`.
+    pub fn render(&self, prompt: &str) -> String {
+        let (prompt, text, back) = match &self.search {
+            Some(s) => {
+                let found = s.found.map_or("", |i| self.history[i].as_str());
+                let prompt = format!("(reverse-i-search)`{}': ", s.query);
+                (prompt, found.to_string(), 0)
+            }
+            None => {
+                (prompt.to_string(), self.text(), self.buf.len() - self.pos)
+            }
+        };
+        let mut s = format!("\r\x1b[K{}{}", prompt, text);
+        if back > 0 {
+            s.push_str(&format!("\x1b[{}D", back));
+        }
+        s
+    }
+}
+
+/// Return the index of the last entry of `history` before `end` which
+/// contains `query`.
+fn find(history: &[String], query: &str, end: usize) -> Option<usize> {
+    history[..end.min(history.len())]
+        .iter()
+        .rposition(|s| s.contains(query))
+}
+
+/// Puts the terminal in raw mode until dropped.
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enable() -> io::Result<RawMode> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "-isig", "-ixon", "min", "1"])?;
+        Ok(RawMode {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let out = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if !out.status.success() {
+        return Err(io::Error::other("stty failed"));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// A line editor with a history saved in a file.
+pub struct Editor {
+    history: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl Editor {
+    /// Create an editor with the history read from `path` if it exists.
+    pub fn new(path: Option<PathBuf>) -> Editor {
+        let mut history: Vec<String> = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .map(|s| s.lines().map(String::from).collect())
+            .unwrap_or_default();
+        let n = history.len().saturating_sub(HISTORY_SIZE);
+        history.drain(..n);
+        Editor { history, path }
+    }
+
+    /// Read a line after `prompt` completing words from `words`, or return
+    /// `None` at the end of input. Falls back to reading a plain line if
+    /// the terminal cannot be put in raw mode.
+    pub fn read_line(
+        &mut self,
+        prompt: &str,
+        words: &[String],
+    ) -> io::Result<Option<String>> {
+        let raw = match RawMode::enable() {
+            Ok(raw) => raw,
+            Err(_) => return read_plain_line(prompt),
+        };
+        let mut stdin = io::stdin().lock();
+        let mut stdout = io::stdout().lock();
+        let mut line = Line::new(&self.history);
+        write!(stdout, "{}", line.render(prompt))?;
+        stdout.flush()?;
+        let r = loop {
+            let key = match read_key(&mut stdin)? {
+                Some(key) => key,
+                None => break None,
+            };
+            match line.handle(key, words) {
+                Action::Continue => {}
+                Action::List(v) => writeln!(stdout, "\n{}", v.join("  "))?,
+                Action::Done(s) => break Some(s),
+                Action::Eof => break None,
+            }
+            write!(stdout, "{}", line.render(prompt))?;
+            stdout.flush()?;
+        };
+        writeln!(stdout)?;
+        drop(raw);
+        Ok(r)
+    }
+
+    /// Add `line` to the history and append it to the history file.
+    pub fn add_history(&mut self, line: &str) {
+        if line.trim().is_empty()
+            || self.history.last().is_some_and(|l| l == line)
+        {
+            return;
+        }
+        self.history.push(line.to_string());
+        if self.history.len() > HISTORY_SIZE {
+            self.history.remove(0);
+        }
+        // The history is a convenience, so failing to save it is ignored.
+        if let Some(path) = &self.path {
+            let _ = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut f| writeln!(f, "{}", line));
+        }
+    }
+}
+
+fn read_plain_line(prompt: &str) -> io::Result<Option<String>> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    match io::stdin().lock().read_line(&mut input)? {
+        0 => Ok(None),
+        _ => Ok(Some(input.trim_end_matches('\n').to_string())),
+    }
+}
@@ -0,0 +1,127 @@
+use super::*;
+
+fn keys(s: &str) -> Vec<Key> {
+    s.chars().map(Key::Char).collect()
+}
+
+#[test]
+fn test_read_key() {
+    let tests: [(&[u8], Vec<Key>); 4] = [
+        (b"ab\r", vec![Key::Char('a'), Key::Char('b'), Key::Enter]),
+        (
+            b"\x1b[A\x1b[B\x1b[C\x1b[D",
+            vec![Key::Up, Key::Down, Key::Right, Key::Left],
+        ),
+        (
+            b"\x1b[3~\x1b[H\x1bOF\x7f\x12\t",
+            vec![
+                Key::Delete,
+                Key::Home,
+                Key::End,
+                Key::Backspace,
+                Key::Search,
+                Key::Tab,
+            ],
+        ),
+        ("θ\x03".as_bytes(), vec![Key::Char('θ'), Key::Interrupt]),
+    ];
+
+    for (input, expected) in tests {
+        let mut r = input;
+        let mut v = Vec::new();
+        while let Some(key) = read_key(&mut r).unwrap() {
+            v.push(key);
+        }
+        assert_eq!(v, expected);
+    }
+}
+
+#[test]
+fn test_line_edit() {
+    let tests = [
+        (vec![Key::Left, Key::Left, Key::Char('+')], "x+^2"),
+        (vec![Key::Home, Key::Delete, Key::End, Key::Backspace], "^"),
+        (vec![Key::Home, Key::Right, Key::KillEnd], "x"),
+        (vec![Key::Left, Key::KillStart, Key::Char('y')], "y2"),
+        (vec![Key::Up, Key::Up, Key::Down], "b"),
+        (vec![Key::Up, Key::Down, Key::Down], "x^2"),
+        (vec![Key::Up, Key::Up, Key::Up, Key::Up], "a"),
+    ];
+
+    let history = [String::from("a"), String::from("b")];
+    for (input, expected) in tests {
+        let mut line = Line::new(&history);
+        for key in keys("x^2").into_iter().chain(input) {
+            assert_eq!(line.handle(key, &[]), Action::Continue);
+        }
+        assert_eq!(line.handle(Key::Enter, &[]), Action::Done(expected.into()));
+    }
+}
+
+#[test]
+fn test_line_search() {
+    let history: Vec<String> = ["\\diff(x^2, x)", "y + 1", "\\diff(y, x)"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let tests = [
+        (keys("diff"), "\\diff(y, x)"),
+        ([keys("diff"), vec![Key::Search]].concat(), "\\diff(x^2, x)"),
+        ([keys("y"), vec![Key::Search]].concat(), "y + 1"),
+        (keys("zz"), ""),
+        (
+            [keys("+"), vec![Key::End, Key::Char('2')]].concat(),
+            "y + 12",
+        ),
+        ([keys("y"), vec![Key::Escape]].concat(), ""),
+    ];
+
+    for (input, expected) in tests {
+        let mut line = Line::new(&history);
+        line.handle(Key::Search, &[]);
+        for key in input {
+            line.handle(key, &[]);
+        }
+        let out = line.handle(Key::Enter, &[]);
+        assert_eq!(out, Action::Done(expected.into()));
+    }
+}
+
+#[test]
+fn test_line_complete() {
+    let words: Vec<String> = ["\\diff", "\\dsolve", "\\sin", "x_1", "x_2"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let tests = [
+        ("\\si", Action::Continue, "\\sin"),
+        ("2 \\di", Action::Continue, "2 \\diff"),
+        ("(x", Action::Continue, "(x_"),
+        (
+            "\\d",
+            Action::List(vec!["\\diff".into(), "\\dsolve".into()]),
+            "\\d",
+        ),
+        ("\\cos", Action::Continue, "\\cos"),
+    ];
+
+    for (input, action, expected) in tests {
+        let mut line = Line::new(&[]);
+        for key in keys(input) {
+            line.handle(key, &words);
+        }
+        assert_eq!(line.handle(Key::Tab, &words), action);
+        assert_eq!(line.text(), expected);
+    }
+}
+
+#[test]
+fn test_line_render() {
+    let mut line = Line::new(&[]);
+    for key in keys("ab") {
+        line.handle(key, &[]);
+    }
+    assert_eq!(line.render(">> "), "\r\x1b[K>> ab");
+    line.handle(Key::Left, &[]);
+    assert_eq!(line.render(">> "), "\r\x1b[K>> ab\x1b[1D");
+}
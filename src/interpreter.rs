@@ -1,6 +1,9 @@
 pub mod ast;
+pub mod command;
 pub mod environment;
 pub mod evaluator;
+pub mod history;
 pub mod lexer;
 pub mod parser;
+pub mod script;
 pub mod token;
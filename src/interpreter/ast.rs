@@ -1,17 +1,66 @@
+use super::token::Span;
 use std::fmt;
 
-pub enum Stmt<'input> {
+pub struct Stmt<'input> {
+    pub kind: StmtKind<'input>,
+    pub span: Span,
+    /// Text of the statement in the input.
+    pub source: &'input str,
+}
+
+pub enum StmtKind<'input> {
     Empty,
     Expr(Expr<'input>),
     Let(Expr<'input>, Expr<'input>),
+    Var(Vec<&'input str>),
+    Command(Command<'input>),
+}
+
+/// A command which inspects or changes the environment.
+pub enum Command<'input> {
+    /// `\vars`
+    Vars,
+    /// `\unset x, y`
+    Unset(Vec<&'input str>),
+    /// `\clear`
+    Clear,
+    /// `\help` or `\help \diff`
+    Help(Option<&'input str>),
+    /// `\type expr`
+    Type(Expr<'input>),
+    /// `\pretty expr` or `\pretty \unicode expr`
+    Pretty(Expr<'input>, bool),
+    /// `\tex expr`
+    Tex(Expr<'input>),
+    /// `\format` or `\format latex`
+    Format(Option<&'input str>),
+    /// `\reset`
+    Reset,
+    /// `\save file` or `\save file \history`
+    Save(&'input str, bool),
+    /// `\load file`
+    Load(&'input str),
+}
+
+pub struct Expr<'input> {
+    pub kind: ExprKind<'input>,
+    pub span: Span,
 }
 
-pub enum Expr<'input> {
+impl<'input> Expr<'input> {
+    pub fn new(kind: ExprKind<'input>, span: Span) -> Expr<'input> {
+        Expr { kind, span }
+    }
+}
+
+pub enum ExprKind<'input> {
     Num(&'input str),
     Ident(&'input str),
     Unary(UnOp, Box<Expr<'input>>),
     Binary(BinOp, Box<Expr<'input>>, Box<Expr<'input>>),
     List(Vec<Expr<'input>>),
+    Call(&'input str, Vec<Expr<'input>>),
+    Lambda(Box<Expr<'input>>, Box<Expr<'input>>),
 }
 
 pub enum UnOp {
@@ -25,40 +74,76 @@ pub enum BinOp {
     Div,
     ImpliedMul,
     Pow,
+    Eq,
 }
 
 impl fmt::Debug for Stmt<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            StmtKind::Empty => Ok(()),
+            StmtKind::Expr(e) => write!(f, "{:?}", e),
+            StmtKind::Let(l, r) => write!(f, "\\let ({:?}) = ({:?})", l, r),
+            StmtKind::Var(v) => write!(f, "\\var {}", v.join(", ")),
+            StmtKind::Command(c) => write!(f, "{:?}", c),
+        }
+    }
+}
+
+impl fmt::Debug for Command<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Stmt::Empty => Ok(()),
-            Stmt::Expr(e) => write!(f, "{:?}", e),
-            Stmt::Let(l, r) => write!(f, "\\let ({:?}) = ({:?})", l, r),
+            Command::Vars => write!(f, "\\vars"),
+            Command::Unset(v) => write!(f, "\\unset {}", v.join(", ")),
+            Command::Clear => write!(f, "\\clear"),
+            Command::Help(None) => write!(f, "\\help"),
+            Command::Help(Some(s)) => write!(f, "\\help {}", s),
+            Command::Type(e) => write!(f, "\\type {:?}", e),
+            Command::Pretty(e, false) => write!(f, "\\pretty {:?}", e),
+            Command::Pretty(e, true) => {
+                write!(f, "\\pretty \\unicode {:?}", e)
+            }
+            Command::Tex(e) => write!(f, "\\tex {:?}", e),
+            Command::Format(None) => write!(f, "\\format"),
+            Command::Format(Some(s)) => write!(f, "\\format {}", s),
+            Command::Reset => write!(f, "\\reset"),
+            Command::Save(p, false) => write!(f, "\\save {}", p),
+            Command::Save(p, true) => write!(f, "\\save {} \\history", p),
+            Command::Load(p) => write!(f, "\\load {}", p),
         }
     }
 }
 
 impl fmt::Debug for Expr<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            &Expr::Num(s) | &Expr::Ident(s) => write!(f, "{}", s),
-            Expr::Unary(op, e) => write!(f, "({:?} {:?})", op, **e),
-            Expr::Binary(op, l, r) => {
+        match &self.kind {
+            ExprKind::Num(s) | ExprKind::Ident(s) => write!(f, "{}", s),
+            ExprKind::Unary(op, e) => write!(f, "({:?} {:?})", op, **e),
+            ExprKind::Binary(op, l, r) => {
                 write!(f, "({:?} {:?} {:?})", **l, op, **r)
             }
-            Expr::List(v) => {
-                let mut i = v.iter();
-                if let Some(e) = i.next() {
-                    let mut r = write!(f, "({:?}", e);
-                    for e in i {
-                        r = r.and_then(|_| write!(f, ", {:?}", e));
-                    }
-                    r = r.and_then(|_| write!(f, ")"));
-                    r
-                } else {
-                    Ok(())
-                }
+            ExprKind::Lambda(p, b) => {
+                write!(f, "({:?} \\to {:?})", **p, **b)
             }
+            ExprKind::List(v) => fmt_args(v, f),
+            ExprKind::Call(name, v) => {
+                write!(f, "{}", name)?;
+                fmt_args(v, f)
+            }
+        }
+    }
+}
+
+fn fmt_args(v: &[Expr], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mut i = v.iter();
+    if let Some(e) = i.next() {
+        let mut r = write!(f, "({:?}", e);
+        for e in i {
+            r = r.and_then(|_| write!(f, ", {:?}", e));
         }
+        r = r.and_then(|_| write!(f, ")"));
+        r
+    } else {
+        Ok(())
     }
 }
 
@@ -79,6 +164,7 @@ impl fmt::Debug for BinOp {
             BinOp::Div => write!(f, "/"),
             BinOp::ImpliedMul => write!(f, "im"),
             BinOp::Pow => write!(f, "^"),
+            BinOp::Eq => write!(f, "="),
         }
     }
 }
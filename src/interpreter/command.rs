@@ -0,0 +1,196 @@
+use super::ast::Command;
+use super::environment::Environment;
+use super::evaluator::{eval_expr, EvalError};
+use super::script;
+use crate::math::expr::Expr;
+use crate::math::format::{pretty, Format};
+use std::fmt::Write;
+use std::fs;
+
+#[cfg(test)]
+mod test;
+
+/// Usage of the commands, shown by `\help` with the builtins.
+const HELP: [(&str, &str); 11] = [
+    ("\\clear", "\\clear: remove the variables and functions"),
+    (
+        "\\format",
+        "\\format name: notation of results, such as latex or mathml",
+    ),
+    ("\\help", "\\help \\f: usage of the builtin or command \\f"),
+    ("\\load", "\\load file: evaluate the statements in file"),
+    (
+        "\\pretty",
+        "\\pretty [\\unicode] e: value of e drawn on several lines",
+    ),
+    (
+        "\\reset",
+        "\\reset: restore the initial environment and history",
+    ),
+    (
+        "\\save",
+        "\\save file [\\history]: write the bindings and history to file",
+    ),
+    ("\\tex", "\\tex e: LaTeX markup of the value of e"),
+    ("\\type", "\\type e: tree of the value of e"),
+    ("\\unset", "\\unset x, y: remove the bindings of x and y"),
+    ("\\vars", "\\vars: list the variables and functions"),
+];
+
+pub fn run(cmd: Command, env: &mut Environment) -> Result<String, EvalError> {
+    match cmd {
+        Command::Vars => {
+            let v: Vec<_> = env
+                .user_bindings()
+                .into_iter()
+                .map(|(s, e)| format!("{} = {:?}", s, e))
+                .collect();
+            Ok(v.join("\n"))
+        }
+        Command::Unset(v) => {
+            for s in &v {
+                if !env.unset(s) {
+                    let msg = format!("{} is not bound", s);
+                    return Err(EvalError::undefined(msg));
+                }
+            }
+            Ok(format!("\\unset {}", v.join(", ")))
+        }
+        Command::Clear => {
+            env.clear();
+            Ok(String::new())
+        }
+        Command::Help(None) => {
+            let mut v: Vec<_> =
+                env.builtins().iter().map(|b| b.help()).collect();
+            v.extend(HELP.iter().map(|&(_, h)| h));
+            Ok(v.join("\n"))
+        }
+        Command::Help(Some(s)) => {
+            let builtin = env.builtins().into_iter().find(|b| b.name() == s);
+            match builtin.map(|b| b.help()) {
+                Some(h) => Ok(h.to_string()),
+                None => HELP
+                    .iter()
+                    .find(|&&(c, _)| c == s)
+                    .map(|&(_, h)| h.to_string())
+                    .ok_or_else(|| {
+                        EvalError::undefined(format!("no help for {}", s))
+                    }),
+            }
+        }
+        Command::Type(e) => {
+            let mut out = String::new();
+            tree(&eval_expr(e, env)?, 0, &mut out);
+            out.pop();
+            Ok(out)
+        }
+        Command::Pretty(e, unicode) => {
+            Ok(pretty::pretty(&eval_expr(e, env)?, unicode))
+        }
+        Command::Tex(e) => Ok(eval_expr(e, env)?.to_latex()),
+        Command::Format(None) => {
+            let v: Vec<_> = Format::ALL.iter().map(|f| f.name()).collect();
+            Ok(format!(
+                "format: {} (choices: {})",
+                env.format().name(),
+                v.join(", ")
+            ))
+        }
+        Command::Format(Some(s)) => {
+            let f = Format::from_name(s).ok_or_else(|| {
+                EvalError::undefined(format!("no format {}", s))
+            })?;
+            env.set_format(f);
+            Ok(format!("\\format {}", f.name()))
+        }
+        Command::Reset => {
+            // The lexer mode and the output format are options of the
+            // session, not bindings.
+            let multi_letter = env.names().multi_letter;
+            let format = env.format();
+            *env = Environment::default();
+            env.names_mut().multi_letter = multi_letter;
+            env.set_format(format);
+            Ok(String::new())
+        }
+        Command::Save(path, history) => {
+            fs::write(path, session(env, history))
+                .map_err(|e| EvalError::file(script::file_error(path, &e)))?;
+            Ok(format!("saved {}", path))
+        }
+        Command::Load(path) => {
+            let input = fs::read_to_string(path)
+                .map_err(|e| EvalError::file(script::file_error(path, &e)))?;
+            script::run(&input, env, &mut |_| {})
+                .map_err(|e| EvalError::file(format!("{}: {}", path, e)))?;
+            Ok(format!("loaded {}", path))
+        }
+    }
+}
+
+/// Return statements which restore the declared names and the bindings
+/// and, if `history` is set, the history and `\ans`.
+fn session(env: &Environment, history: bool) -> String {
+    let mut s = String::new();
+    let names: Vec<_> = env.names().declared().collect();
+    if !names.is_empty() {
+        writeln!(s, "\\var {}", names.join(", ")).unwrap();
+    }
+    if history {
+        for e in env.history() {
+            writeln!(s, "{}", e.input).unwrap();
+        }
+        // Drop the bindings made by the history.
+        writeln!(s, "\\clear").unwrap();
+    }
+    let (ans, mut vars): (Vec<_>, Vec<_>) = env
+        .user_bindings()
+        .into_iter()
+        .partition(|&(name, _)| name == "\\ans");
+    // A value mentioning a bound name goes before the binding of the name,
+    // which would otherwise replace it when the value is read back.
+    while !vars.is_empty() {
+        let i = (0..vars.len())
+            .find(|&i| {
+                let name = vars[i].0;
+                vars.iter().all(|&(n, e)| n == name || !e.has_sym(name))
+            })
+            .unwrap_or(0);
+        let (name, e) = vars.remove(i);
+        writeln!(s, "\\let {} = {}", name, e).unwrap();
+    }
+    // `\ans` goes last since every binding changes it.
+    if history {
+        for (name, e) in ans {
+            writeln!(s, "\\let {} = {}", name, e).unwrap();
+        }
+    }
+    s
+}
+
+/// Write the variants of `e` and its subexpressions indented by `depth`.
+fn tree(e: &Expr, depth: usize, out: &mut String) {
+    let (label, args) = match e {
+        Expr::Num(n) => (format!("Num {:?}", n), vec![]),
+        Expr::Sym(s) => (format!("Sym {}", s), vec![]),
+        Expr::Add(a) => ("Add".to_string(), a.clone().into_args()),
+        Expr::Mul(m) => ("Mul".to_string(), m.clone().into_args()),
+        Expr::Pow(b, e) => ("Pow".to_string(), vec![*b.clone(), *e.clone()]),
+        Expr::Vec(v) => ("Vec".to_string(), v.clone()),
+        Expr::Cmd(c) => (format!("Cmd {}", c.name()), vec![]),
+        Expr::Call(f, a) => ("Call".to_string(), vec![*f.clone(), *a.clone()]),
+        Expr::Lambda(p, b) => {
+            (format!("Lambda {}", p.join(", ")), vec![*b.clone()])
+        }
+        Expr::Series(_) => (format!("Series {:?}", e), vec![]),
+        Expr::Inf(s) => (format!("Inf {:?}", s), vec![]),
+        Expr::Eq(l, r) => ("Eq".to_string(), vec![*l.clone(), *r.clone()]),
+    };
+    out.push_str(&"  ".repeat(depth));
+    out.push_str(&label);
+    out.push('\n');
+    for a in &args {
+        tree(a, depth + 1, out);
+    }
+}
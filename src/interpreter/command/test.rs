@@ -0,0 +1,207 @@
+use super::super::script;
+use super::*;
+
+fn run_all(tests: &[(&str, &str)], env: &mut Environment) {
+    for &(input, expected) in tests {
+        let mut out = Vec::new();
+        let r = script::run(input, env, &mut |s| out.push(s));
+        let out = match r {
+            Ok(()) => out.join("\n"),
+            Err(e) => e.message.lines().last().unwrap().to_string(),
+        };
+        assert_eq!(out, expected);
+    }
+}
+
+#[test]
+fn test_vars() {
+    let tests = [
+        (r"\vars", ""),
+        (
+            r"\let a = 2; \let f(x) = x^a; \let \infty = 0",
+            r"\let \infty = 0",
+        ),
+        (
+            r"\vars",
+            "\\ans = 0\n\\infty = 0\na = 2\nf = (x \\to (x ^ 2))",
+        ),
+        (r"\unset a, \ans", r"\unset a, \ans"),
+        (r"\unset a", r"a is not bound"),
+        ("a", "a"),
+        (r"\clear; \vars", ""),
+        (r"\infty + f(2)", "\\infty"),
+        (
+            r"\help \diff",
+            r"\diff(f, x): derivative of f with respect to x",
+        ),
+        (
+            r"\help \unset",
+            r"\unset x, y: remove the bindings of x and y",
+        ),
+        (r"\help \foo", r"no help for \foo"),
+    ];
+
+    run_all(&tests, &mut Environment::default());
+}
+
+#[test]
+fn test_reset() {
+    let mut env = Environment::default();
+    env.names_mut().multi_letter = true;
+    let tests = [
+        (r"\let ab = 2; \let \exp = 3", r"\let \exp = 3"),
+        (r"\reset", ""),
+        (r"ab + \exp(0)", "(ab + 1)"),
+    ];
+
+    run_all(&tests, &mut env);
+    assert_eq!(env.history().len(), 1);
+}
+
+#[test]
+fn test_type() {
+    let tests = [
+        (r"\type 2 x^2 + 1/2", "Add\n  Mul\n    Num 2\n    Pow\n      Sym x\n      Num 2\n  Num 1/2"),
+        (r"\type (y \to \sin(y), -\infty)", "Vec\n  Lambda y\n    Call\n      Cmd \\sin\n      Sym y\n  Inf Neg"),
+    ];
+
+    run_all(&tests, &mut Environment::default());
+}
+
+#[test]
+fn test_pretty() {
+    let tests = [
+        (r"\let y = x^2/2", r"\let y = (1/2 * (x ^ 2))"),
+        (r"\pretty y + 1", " 2\nx\n-- + 1\n2"),
+        (r"\pretty \unicode -y", "   2\n  x\n- ──\n  2"),
+    ];
+
+    run_all(&tests, &mut Environment::default());
+}
+
+#[test]
+fn test_tex() {
+    let tests = [
+        (r"\tex(x^2/2 - 1)", r"\frac{x^{2}}{2} - 1"),
+        (r"\tex \sin(x)^(1/2)", r"\sqrt{\sin\left(x\right)}"),
+    ];
+
+    run_all(&tests, &mut Environment::default());
+}
+
+#[test]
+fn test_format() {
+    let tests = [
+        (
+            r"\format",
+            "format: plain (choices: plain, infix, pretty, unicode, latex, mathml, asciimath)",
+        ),
+        (r"\format asciimath", r"\format asciimath"),
+        ("x^2/2 + \\sqrt{y}", "x^2/2 + sqrt(y)"),
+        (r"\format MathML", r"\format mathml"),
+        (
+            "-x",
+            r#"<math xmlns="http://www.w3.org/1998/Math/MathML"><mrow><mo>-</mo><mi>x</mi></mrow></math>"#,
+        ),
+        (r"\let y = 2x", r"\let y = (2 * x)"),
+        (r"\format latex; \reset; y/3", r"\frac{y}{3}"),
+        (r"\format tex", "no format tex"),
+    ];
+
+    run_all(&tests, &mut Environment::default());
+}
+
+#[test]
+fn test_save_load() {
+    let path = std::env::temp_dir().join("sym_calc_test_session.sc");
+    let path = path.to_str().unwrap();
+    let save = format!(r"\save {} \history", path);
+    let load = format!(r"\load {}", path);
+    let mut env = Environment::default();
+    let tests = [
+        (r"\var ab", r"\var ab"),
+        (
+            r"\let ab = 2; \let f(x, y) = x^ab y",
+            r"\let f(x, y) = ((x ^ 2) * y)",
+        ),
+        (r"\diff(f(t, 1), t)", "(2 * t)"),
+        (r"\let ab = 3", r"\let ab = 3"),
+        (&save, &format!("saved {}", path)),
+    ];
+    run_all(&tests, &mut env);
+
+    let saved = fs::read_to_string(path).unwrap();
+    let expected = [
+        r"\var ab",
+        r"\let ab = 2",
+        r"\let f(x, y) = x^ab y",
+        r"\diff(f(t, 1), t)",
+        r"\let ab = 3",
+        r"\clear",
+        r"\let ab = 3",
+        r"\let f = (x, y) \to x^2*y",
+        r"\let \ans = 3",
+        "",
+    ];
+    assert_eq!(saved, expected.join("\n"));
+
+    let mut loaded = Environment::default();
+    let tests = [
+        (load.as_str(), format!("loaded {}", path)),
+        (r"ab f(1, 1)", "3".to_string()),
+        (r"\out(3)", "(2 * t)".to_string()),
+    ];
+    for (input, expected) in &tests {
+        run_all(&[(input, expected)], &mut loaded);
+    }
+    // The first binding is `\ans`, which the statements above changed.
+    assert_eq!(loaded.user_bindings()[1..], env.user_bindings()[1..]);
+    fs::remove_file(path).unwrap();
+
+    let mut out = Vec::new();
+    let r = script::run(&load, &mut loaded, &mut |s| out.push(s));
+    assert!(r.is_err());
+}
+
+#[test]
+fn test_save_load_round_trip() {
+    let path = std::env::temp_dir().join("sym_calc_test_round_trip.sc");
+    let path = path.to_str().unwrap();
+    let mut env = Environment::default();
+    let lets = [
+        r"\let a = -3/4",
+        r"\let b = x",
+        r"\let c = x + 2y",
+        r"\let d = 2 x y",
+        r"\let e = x^(1/2)",
+        r"\let v = (1, (2, x))",
+        r"\let s = \sin",
+        r"\let u = \sum(1/k, k, 1, n)",
+        r"\let f = (x, y) \to x y",
+        r"\let g = \series(\exp(x), x, 1, 2)",
+        r"\let i = -\infty",
+        r"\let q = x^2 = y + 1",
+        r"\let z = w",
+        r"\let w = 2",
+    ];
+    for input in lets {
+        script::run(input, &mut env, &mut |_| {}).unwrap();
+    }
+    let save = format!(r"\save {}", path);
+    run_all(&[(&save, &format!("saved {}", path))], &mut env);
+    let saved = fs::read_to_string(path).unwrap();
+    assert!(!saved.contains(r"\ans"));
+
+    let mut loaded = Environment::default();
+    let load = format!(r"\load {}", path);
+    run_all(&[(&load, &format!("loaded {}", path))], &mut loaded);
+    fs::remove_file(path).unwrap();
+    let bindings = |env: &Environment| {
+        let mut v = env.user_bindings();
+        v.retain(|&(name, _)| name != r"\ans");
+        v.into_iter()
+            .map(|(name, e)| (name.to_string(), e.clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(bindings(&loaded), bindings(&env));
+}
@@ -1,21 +1,167 @@
-use crate::math::expr::Expr;
+use super::history::{self, Entry};
+use super::lexer::Names;
+use crate::math::builtin::{self, Builtin, Cmd};
+use crate::math::expr::{Expr, Sign};
+use crate::math::format::Format;
 use std::collections::HashMap;
 
+#[derive(Clone)]
 pub struct Environment {
     store: HashMap<String, Expr>,
+    names: Names,
+    history: Vec<Entry>,
+    format: Format,
+    /// Parameters of the functions whose bodies are being evaluated.
+    params: Vec<String>,
+}
+
+impl Default for Environment {
+    fn default() -> Environment {
+        let mut env = Environment::new();
+        for b in builtin::defaults() {
+            env.register(b);
+        }
+        env.register(&history::OUT);
+        env.register(&history::IN);
+        env.set("\\infty", Expr::Inf(Sign::Pos));
+        env
+    }
 }
 
 impl Environment {
     pub fn new() -> Environment {
         Environment {
             store: HashMap::new(),
+            names: Names::default(),
+            history: Vec::new(),
+            format: Format::default(),
+            params: Vec::new(),
         }
     }
 
-    pub fn default() -> Environment {
-        let mut env = Environment::new();
-        env.set("\\diff", crate::math::diff::CMD_DIFF);
-        env
+    /// Bind the builtin to its name.
+    pub fn register(&mut self, b: &'static dyn Builtin) {
+        self.set(b.name(), Expr::Cmd(Cmd(b)));
+    }
+
+    /// Return the bound builtins ordered by name.
+    pub fn builtins(&self) -> Vec<&'static dyn Builtin> {
+        let mut v: Vec<_> = self
+            .store
+            .iter()
+            .filter_map(|(s, e)| match e {
+                // Skip other names bound to a builtin such as `\ans`.
+                Expr::Cmd(c) if c.name() == s => Some(c.0),
+                _ => None,
+            })
+            .collect();
+        v.sort_by_key(|b| b.name());
+        v
+    }
+
+    /// Return the bindings made by the user ordered by name, leaving out
+    /// builtins and unchanged bindings of `Environment::default`.
+    pub fn user_bindings(&self) -> Vec<(&str, &Expr)> {
+        let defaults = Environment::default();
+        let mut v: Vec<_> = self
+            .store
+            .iter()
+            .filter(|&(s, e)| match e {
+                Expr::Cmd(c) => c.name() != s,
+                e => defaults.store.get(s) != Some(e),
+            })
+            .map(|(s, e)| (s.as_str(), e))
+            .collect();
+        v.sort_unstable_by_key(|&(s, _)| s);
+        v
+    }
+
+    /// Remove the binding of `s` and return whether there was one.
+    pub fn unset(&mut self, s: &str) -> bool {
+        self.store.remove(s).is_some()
+    }
+
+    /// Remove the bindings made by the user, keeping the builtins, the
+    /// history and the declared names.
+    pub fn clear(&mut self) {
+        let defaults = Environment::default();
+        self.store.retain(|s, e| match e {
+            Expr::Cmd(c) => c.name() == s,
+            e => defaults.store.get(s) == Some(e),
+        });
+        for (s, e) in defaults.store {
+            self.store.entry(s).or_insert(e);
+        }
+    }
+
+    /// Return the bound names in order.
+    pub fn bound_names(&self) -> Vec<&str> {
+        let mut v: Vec<_> = self.store.keys().map(String::as_str).collect();
+        v.sort_unstable();
+        v
+    }
+
+    /// Return the identifiers the lexer should read as one name.
+    pub fn names(&self) -> &Names {
+        &self.names
+    }
+
+    pub fn names_mut(&mut self) -> &mut Names {
+        &mut self.names
+    }
+
+    /// Return the notation of the results of expression statements.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn set_format(&mut self, f: Format) {
+        self.format = f;
+    }
+
+    /// Add a statement and its result to the history and bind the result
+    /// to `\ans`.
+    pub fn record(&mut self, input: &str, output: Expr) {
+        self.set("\\ans", output.clone());
+        self.history.push(Entry {
+            input: input.to_string(),
+            output,
+        });
+    }
+
+    pub fn history(&self) -> &[Entry] {
+        &self.history
+    }
+
+    /// Forget the entries after the first `len`.
+    pub fn truncate_history(&mut self, len: usize) {
+        self.history.truncate(len);
+    }
+
+    /// Return the index and the entry `n` of the history counting from 1,
+    /// or the `-n`-th last entry if `n` is negative.
+    pub fn entry(&self, n: i32) -> Option<(usize, &Entry)> {
+        let i = if n < 0 {
+            self.history.len().checked_sub(n.unsigned_abs() as usize)?
+        } else {
+            (n as usize).checked_sub(1)?
+        };
+        self.history.get(i).map(|e| (i, e))
+    }
+
+    /// Bind the parameters of a function to their symbols while its body
+    /// is evaluated.
+    pub fn set_params(&mut self, params: &[String]) {
+        for p in params {
+            self.set(p, Expr::Sym(p.clone()));
+            self.params.push(p.clone());
+        }
+    }
+
+    /// Return whether `e` mentions a parameter of a function whose body is
+    /// being evaluated.
+    pub fn has_param(&self, e: &Expr) -> bool {
+        self.params.iter().any(|p| e.has_sym(p))
     }
 
     pub fn set(&mut self, s: &str, e: Expr) {
@@ -1,64 +1,337 @@
-use super::{ast, environment::Environment};
+use super::ast::{self, ExprKind, StmtKind};
+use super::command;
+use super::environment::Environment;
 use crate::math::expr::{Expr, NEG_ONE};
+use crate::math::format;
+use crate::math::num::{self, Num};
+
+pub use crate::math::error::{ErrorKind, EvalError};
 
 #[cfg(test)]
 mod test;
 
-pub fn eval_stmt(stmt: ast::Stmt, env: &mut Environment) -> String {
-    match stmt {
-        ast::Stmt::Empty => String::new(),
-        ast::Stmt::Expr(e) => {
-            let e = eval_expr(e, env);
-            format!("{:?}", e)
-        }
-        ast::Stmt::Let(l, r) => {
-            let r = eval_expr(r, env);
-            let ret = format!("\\let {:?} = {:?}", l, r);
-            match l {
-                ast::Expr::Ident(s) => env.set(s, r),
-                // ast::Expr::Binary(ast::BinOp::ImpliedMul, l, r) => {
-                //     if let (ast::Expr::Ident(f), ast::Expr::Ident(x)) = (*l, *r)
-                //     {
-                //         todo!()
-                //     }
-                // }
-                _ => {}
+pub fn eval_stmt(
+    stmt: ast::Stmt,
+    env: &mut Environment,
+) -> Result<String, EvalError> {
+    let source = stmt.source;
+    // Forget overflows while printing the previous result.
+    num::take_overflow();
+    match stmt.kind {
+        StmtKind::Empty => Ok(String::new()),
+        StmtKind::Var(v) => {
+            for s in &v {
+                env.names_mut().declare(s);
             }
-            ret
+            Ok(format!("\\var {}", v.join(", ")))
+        }
+        StmtKind::Command(c) => command::run(c, env),
+        StmtKind::Expr(e) => {
+            let e = eval_expr(e, env)?;
+            let ret = format::render(&e, env.format());
+            env.record(source, e);
+            Ok(ret)
         }
+        StmtKind::Let(l, r) => match l.kind {
+            ExprKind::Call(f, params) => {
+                let span = l.span;
+                let params = param_names(params).ok_or_else(|| {
+                    EvalError::type_mismatch("invalid function definition")
+                        .at(span)
+                })?;
+                let body = eval_body(r, &params, env)?;
+                let ret =
+                    format!("\\let {}({}) = {:?}", f, params.join(", "), body);
+                let lambda = Expr::Lambda(params, Box::new(body));
+                env.set(f, lambda.clone());
+                env.record(source, lambda);
+                Ok(ret)
+            }
+            kind => {
+                let r = eval_expr(r, env)?;
+                let l = ast::Expr::new(kind, l.span);
+                let ret = format!("\\let {:?} = {:?}", l, r);
+                if let ExprKind::Ident(s) = l.kind {
+                    env.set(s, r.clone());
+                }
+                env.record(source, r);
+                Ok(ret)
+            }
+        },
     }
 }
 
-pub fn eval_expr(e: ast::Expr, env: &Environment) -> Expr {
-    match e {
-        ast::Expr::Num(s) => {
-            let n: i32 = s.parse().unwrap();
-            Expr::int(n)
-        }
-        ast::Expr::Ident(s) => env.get(s),
-        ast::Expr::Unary(op, expr) => match op {
-            ast::UnOp::Neg => NEG_ONE * eval_expr(*expr, env),
-        },
-        ast::Expr::Binary(op, left, right) => match op {
-            ast::BinOp::Add => eval_expr(*left, env) + eval_expr(*right, env),
-            ast::BinOp::Sub => eval_expr(*left, env) - eval_expr(*right, env),
-            ast::BinOp::Mul => eval_expr(*left, env) * eval_expr(*right, env),
-            ast::BinOp::ImpliedMul => {
-                let l = eval_expr(*left, env);
-                let r = eval_expr(*right, env);
-                match l {
-                    f @ Expr::Cmd(..) => Expr::call(f, r),
-                    _ => l * r,
-                }
+/// Return the names of the parameters in `f(x)` or `f(x, y, ...)`, or
+/// `None` if one is not a name or occurs twice.
+fn param_names(v: Vec<ast::Expr>) -> Option<Vec<String>> {
+    let mut params: Vec<String> = Vec::new();
+    for e in v {
+        match e.kind {
+            ExprKind::Ident(x) if !params.iter().any(|p| p == x) => {
+                params.push(x.to_string())
             }
-            ast::BinOp::Div => eval_expr(*left, env) / eval_expr(*right, env),
-            ast::BinOp::Pow => {
-                Expr::pow(eval_expr(*left, env), eval_expr(*right, env))
+            _ => return None,
+        }
+    }
+    Some(params)
+}
+
+/// Evaluate the body of a function with its parameters left as symbols.
+fn eval_body(
+    body: ast::Expr,
+    params: &[String],
+    env: &Environment,
+) -> Result<Expr, EvalError> {
+    let mut scope = env.clone();
+    scope.set_params(params);
+    eval_expr(body, &scope)
+}
+
+/// Collect the factors of a product written without `*`.
+fn juxtaposed<'a>(e: ast::Expr<'a>, factors: &mut Vec<ast::Expr<'a>>) {
+    match e.kind {
+        ExprKind::Binary(ast::BinOp::ImpliedMul, l, r) => {
+            juxtaposed(*l, factors);
+            juxtaposed(*r, factors);
+        }
+        _ => factors.push(e),
+    }
+}
+
+/// Multiply juxtaposed factors. A builtin is applied to the product of the
+/// factors after it up to the next function or call, so that
+/// `\sin 2x \cos x` is `\sin(2x) \cos(x)`, and to the value of the next
+/// function if none are in between, as in `\sin \cos x`. A lambda takes
+/// only the next factor, as in `f(1)(2)`. The power of a function raises
+/// its value, so that `\sin^2 x` is `\sin(x)^2`.
+fn eval_juxtaposed(
+    factors: Vec<ast::Expr>,
+    env: &Environment,
+) -> Result<Expr, EvalError> {
+    let mut product = Vec::new();
+    // Functions waiting for an argument with their exponents, innermost
+    // last.
+    let mut funcs: Vec<(Expr, Option<Expr>)> = Vec::new();
+    let mut arg = Vec::new();
+    for e in factors {
+        let call =
+            matches!(e.kind, ExprKind::Call(f, _) if is_func(&env.get(f)));
+        let (mut v, exp) = eval_factor(e, env)?;
+        while !is_func(&v)
+            && matches!(funcs.last(), Some((Expr::Lambda(..), _)))
+        {
+            v = call_pow(funcs.pop().unwrap(), v, env)?;
+        }
+        let func = is_func(&v);
+        if (func || call) && !arg.is_empty() {
+            product.push(apply(&mut funcs, &mut arg, env)?);
+        }
+        if func {
+            funcs.push((v, exp));
+        } else if funcs.is_empty() {
+            product.push(v);
+        } else {
+            arg.push(v);
+        }
+    }
+    if !funcs.is_empty() {
+        product.push(apply(&mut funcs, &mut arg, env)?);
+    }
+    multiply(product)
+}
+
+/// Evaluate a factor, or return a function and its exponent separately if
+/// the factor is a power of a function as in `\sin^2`. Other exponents
+/// than positive integers are rejected since `\sin^{-1}` means the inverse
+/// function in LaTeX.
+fn eval_factor(
+    e: ast::Expr,
+    env: &Environment,
+) -> Result<(Expr, Option<Expr>), EvalError> {
+    let func = |e: &ast::Expr| matches!(e.kind, ExprKind::Ident(f) if is_func(&env.get(f)));
+    match e.kind {
+        ExprKind::Binary(ast::BinOp::Pow, base, exp) if func(&base) => {
+            let span = exp.span;
+            let f = eval_expr(*base, env)?;
+            match eval_expr(*exp, env)? {
+                exp @ Expr::Num(Num::Int(1..)) => Ok((f, Some(exp))),
+                _ => Err(EvalError::domain(
+                    "power of a function must be a positive integer",
+                )
+                .at(span)),
             }
-        },
-        ast::Expr::List(v) => {
-            let v = v.into_iter().map(|e| eval_expr(e, env)).collect();
-            Expr::Vec(v)
+        }
+        _ => Ok((eval_expr(e, env)?, None)),
+    }
+}
+
+/// Call a function and raise its value to the exponent if there is one.
+fn call_pow(
+    (f, exp): (Expr, Option<Expr>),
+    x: Expr,
+    env: &Environment,
+) -> Result<Expr, EvalError> {
+    let v = Expr::call_in(f, x, env)?;
+    match exp {
+        Some(exp) => v.try_pow(exp),
+        None => Ok(v),
+    }
+}
+
+fn is_func(e: &Expr) -> bool {
+    matches!(e, Expr::Cmd(..) | Expr::Lambda(..))
+}
+
+/// Return the product of the nonempty list `v` from left to right.
+fn multiply(v: Vec<Expr>) -> Result<Expr, EvalError> {
+    let mut v = v.into_iter();
+    let first = v.next().unwrap();
+    v.try_fold(first, |p, e| p.try_mul(e))
+}
+
+/// Apply the functions in turn, innermost first, to the product of `arg`
+/// and return the result, or the product of the functions if `arg` is
+/// empty. Both lists are emptied.
+fn apply(
+    funcs: &mut Vec<(Expr, Option<Expr>)>,
+    arg: &mut Vec<Expr>,
+    env: &Environment,
+) -> Result<Expr, EvalError> {
+    if arg.is_empty() {
+        let funcs = funcs
+            .drain(..)
+            .map(|(f, exp)| match exp {
+                Some(exp) => f.try_pow(exp),
+                None => Ok(f),
+            })
+            .collect::<Result<_, _>>()?;
+        return multiply(funcs);
+    }
+    let arg = multiply(std::mem::take(arg))?;
+    funcs
+        .drain(..)
+        .rev()
+        .try_fold(arg, |x, f| call_pow(f, x, env))
+}
+
+/// Replace the calls `y(x)` of the unknown function in the equation of
+/// `\dsolve(eq, y, x, ...)` by `y`, which would otherwise be evaluated as
+/// the product `y x`.
+fn unknown_function(args: &mut [ast::Expr]) {
+    if let [eq, y, x, ..] = args {
+        if let (ExprKind::Ident(y), ExprKind::Ident(x)) = (&y.kind, &x.kind) {
+            replace_calls(eq, y, x);
         }
     }
 }
+
+fn replace_calls<'a>(e: &mut ast::Expr<'a>, y: &'a str, x: &str) {
+    match &mut e.kind {
+        ExprKind::Call(f, args)
+            if *f == y
+                && matches!(
+                    args.as_slice(),
+                    [a] if matches!(a.kind, ExprKind::Ident(s) if s == x)
+                ) =>
+        {
+            e.kind = ExprKind::Ident(y);
+        }
+        ExprKind::Call(_, v) | ExprKind::List(v) => {
+            v.iter_mut().for_each(|e| replace_calls(e, y, x))
+        }
+        ExprKind::Unary(_, e) => replace_calls(e, y, x),
+        ExprKind::Binary(_, l, r) | ExprKind::Lambda(l, r) => {
+            replace_calls(l, y, x);
+            replace_calls(r, y, x);
+        }
+        ExprKind::Num(_) | ExprKind::Ident(_) => {}
+    }
+}
+
+pub fn eval_expr(e: ast::Expr, env: &Environment) -> Result<Expr, EvalError> {
+    let span = e.span;
+    let r = match e.kind {
+        ExprKind::Num(s) => s
+            .parse()
+            .map(Expr::int)
+            .map_err(|_| EvalError::overflow("number too large")),
+        ExprKind::Ident(s) => Ok(env.get(s)),
+        ExprKind::Unary(op, expr) => match op {
+            ast::UnOp::Neg => NEG_ONE.try_mul(eval_expr(*expr, env)?),
+        },
+        ExprKind::Binary(ast::BinOp::ImpliedMul, ..) => {
+            let mut factors = Vec::new();
+            juxtaposed(e, &mut factors);
+            eval_juxtaposed(factors, env)
+        }
+        ExprKind::Binary(op, left, right) => {
+            let l = eval_expr(*left, env)?;
+            let r = eval_expr(*right, env)?;
+            match op {
+                ast::BinOp::Add => l.try_add(r),
+                ast::BinOp::Sub => l.try_sub(r),
+                ast::BinOp::Mul | ast::BinOp::ImpliedMul => l.try_mul(r),
+                ast::BinOp::Div => l.try_div(r),
+                ast::BinOp::Pow => l.try_pow(r),
+                ast::BinOp::Eq => match (l, r) {
+                    (Expr::Eq(..), _) | (_, Expr::Eq(..)) => {
+                        Err(EvalError::type_mismatch("unsupported operand"))
+                    }
+                    (l, r) => Ok(Expr::Eq(Box::new(l), Box::new(r))),
+                },
+            }
+        }
+        ExprKind::Lambda(params, body) => {
+            let span = params.span;
+            let params = match params.kind {
+                ExprKind::List(v) => param_names(v),
+                kind => param_names(vec![ast::Expr::new(kind, span)]),
+            }
+            .ok_or_else(|| {
+                EvalError::type_mismatch("invalid lambda parameters").at(span)
+            })?;
+            let body = eval_body(*body, &params, env)?;
+            Ok(Expr::Lambda(params, Box::new(body)))
+        }
+        ExprKind::List(v) => v
+            .into_iter()
+            .map(|e| eval_expr(e, env))
+            .collect::<Result<_, _>>()
+            .map(Expr::Vec),
+        ExprKind::Call(f, mut args) => {
+            if env.get(f).is_cmd("\\dsolve") {
+                unknown_function(&mut args);
+            }
+            let mut args = args
+                .into_iter()
+                .map(|e| eval_expr(e, env))
+                .collect::<Result<Vec<_>, _>>()?;
+            // `f(x)` is a call only if `f` is bound to a function.
+            match env.get(f) {
+                f @ Expr::Cmd(..) | f @ Expr::Lambda(..) => {
+                    match Expr::call_args(f.clone(), args.clone(), env) {
+                        // A call in the body of a function may only be
+                        // valid once the parameters have values.
+                        Err(_) if args.iter().any(|a| env.has_param(a)) => {
+                            let x = match args.len() {
+                                1 => args.pop().unwrap(),
+                                _ => Expr::Vec(args),
+                            };
+                            Ok(Expr::unevaluated_call(f, x))
+                        }
+                        r => r,
+                    }
+                }
+                f if args.len() == 1 => f.try_mul(args.pop().unwrap()),
+                f => f.try_mul(Expr::Vec(args)),
+            }
+        }
+    };
+    // Operators which cannot return an error record overflows instead.
+    let r = if num::take_overflow() {
+        Err(EvalError::overflow("integer overflow"))
+    } else {
+        r
+    };
+    r.map_err(|e| e.at(span))
+}
@@ -1,4 +1,4 @@
-use super::super::{lexer::Lexer, parser::Parser};
+use super::super::{lexer::Lexer, parser::Parser, token::Span};
 use super::*;
 
 #[test]
@@ -16,9 +16,215 @@ fn test_eval_let_stmt() {
 
     let mut env = Environment::new();
     for (input, expected) in tests {
-        let l = Lexer::new(&input);
+        let l = Lexer::new(input);
         let mut p = Parser::new(l);
-        let stmt = p.parse_stmt();
-        assert_eq!(eval_stmt(stmt, &mut env), expected);
+        let stmt = p.parse_stmt().unwrap();
+        let out = eval_stmt(stmt, &mut env).unwrap_or_else(|e| e.to_string());
+        assert_eq!(out, expected);
     }
 }
+
+#[test]
+fn test_eval_function_def() {
+    let tests = [
+        (r"\let f(x) = x^2 + 1", r"\let f(x) = ((x ^ 2) + 1)"),
+        ("f(3)", "10"),
+        ("f(y + 1)", "(((y + 1) ^ 2) + 1)"),
+        ("f(1, 2)", "arguments error"),
+        (r"\diff(f(x), x)", "(2 * x)"),
+        (r"\let g(x, y) = x y + y", r"\let g(x, y) = (y + (x * y))"),
+        ("g(2, 3)", "9"),
+        ("g(y, x)", "(x + (x * y))"),
+        ("g(1)", "arguments error"),
+        ("g((1, 2))", "arguments error"),
+        (r"\let h(x) = y \to x + y", r"\let h(x) = (y \to (x + y))"),
+        ("h(y)(2)", "(y + 2)"),
+        (r"\let x = 2", r"\let x = 2"),
+        (r"\let h(t) = t + x", r"\let h(t) = (t + 2)"),
+        (r"\let k(x) = x", r"\let k(x) = x"),
+        ("k(5)", "5"),
+        ("f", r"(x \to ((x ^ 2) + 1))"),
+        (r"\let f(2) = 3", "invalid function definition"),
+        (r"\let f(x, x) = x", "invalid function definition"),
+        ("(x, x) \\to x", "invalid lambda parameters"),
+        (
+            r"\let p(n) = \nest(s \to 2 s, 1, n)",
+            r"\let p(n) = \nest(((s \to (2 * s)), 1, n))",
+        ),
+        ("p(3)", "8"),
+    ];
+
+    let mut env = Environment::default();
+    for (input, expected) in tests {
+        let l = Lexer::new(input);
+        let mut p = Parser::new(l);
+        let stmt = p.parse_stmt().unwrap();
+        let out = eval_stmt(stmt, &mut env).unwrap_or_else(|e| e.to_string());
+        assert_eq!(out, expected);
+    }
+}
+
+#[test]
+fn test_eval_call() {
+    let tests = [
+        ("f(x + 1)", "(f * (x + 1))"),
+        (r"3\diff(x^2, x)", "(6 * x)"),
+        (r"\exp(x)^2", r"(\exp(x) ^ 2)"),
+        (r"\let f = x \to x^2", r"\let f = (x \to (x ^ 2))"),
+        ("f(3)", "9"),
+        ("f (3)", "9"),
+        ("2f(y)", "(2 * (y ^ 2))"),
+    ];
+
+    let mut env = Environment::default();
+    for (input, expected) in tests {
+        let l = Lexer::new(input);
+        let mut p = Parser::new(l);
+        let stmt = p.parse_stmt().unwrap();
+        let out = eval_stmt(stmt, &mut env).unwrap_or_else(|e| e.to_string());
+        assert_eq!(out, expected);
+    }
+}
+
+#[test]
+fn test_eval_latex() {
+    let tests = [
+        (r"\frac{x^{2}}{2} + \sqrt{4}", "((1/2 * (x ^ 2)) + 2)"),
+        (
+            r"\sqrt[3]{x} \cdot \left(y + 1\right)",
+            "((x ^ 1/3) * (y + 1))",
+        ),
+        (r"\sin 2x \cos x", r"(\cos(x) * \sin((2 * x)))"),
+        (r"3 \sin \frac{x}{2}", r"(3 * \sin((1/2 * x)))"),
+        (r"\sin \cos x + 1", r"(\sin(\cos(x)) + 1)"),
+        (r"\sin\left(x\right) y", r"(y * \sin(x))"),
+        (
+            r"\sin x \cos(x) \sin \cos(y)",
+            r"(\cos(x) * \sin(x) * \sin(\cos(y)))",
+        ),
+        (r"\let f = x \to \exp x", r"\let f = (x \to \exp(x))"),
+        (r"f 2 \sin", r"(\sin * \exp(2))"),
+        (r"\sin^2 x", r"(\sin(x) ^ 2)"),
+        (r"\sin^{2}\left(x\right)", r"(\sin(x) ^ 2)"),
+        (r"\sin^2 x + \cos^2(x)", r"((\cos(x) ^ 2) + (\sin(x) ^ 2))"),
+        (r"3 \sin^2 \cos x", r"(3 * (\sin(\cos(x)) ^ 2))"),
+        (r"f^2 1", r"(\exp(1) ^ 2)"),
+    ];
+
+    let mut env = Environment::default();
+    for (input, expected) in tests {
+        let l = Lexer::new(input);
+        let mut p = Parser::new(l);
+        let stmt = p.parse_stmt().unwrap();
+        let out = eval_stmt(stmt, &mut env).unwrap_or_else(|e| e.to_string());
+        assert_eq!(out, expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_eval_var_stmt() {
+    let tests = [
+        ("theta v_0", "(a * e * h * (t ^ 2) * v_0)"),
+        (r"\var theta, v_0", r"\var theta, v_0"),
+        ("theta v_0", "(theta * v_0)"),
+        (r"\let theta = 2", r"\let theta = 2"),
+        ("theta^2 + θ", "(θ + 4)"),
+    ];
+
+    let mut env = Environment::default();
+    for (input, expected) in tests {
+        let l = Lexer::new(input).with_names(env.names().clone());
+        let mut p = Parser::new(l);
+        let stmt = p.parse_stmt().unwrap();
+        let out = eval_stmt(stmt, &mut env).unwrap_or_else(|e| e.to_string());
+        assert_eq!(out, expected);
+    }
+}
+
+#[test]
+fn test_eval_error_span() {
+    let tests = [
+        (r"1 + \diff(x^2) * 3", (4, 14)),
+        (r"\let f(2) = 1", (5, 9)),
+        (r"(x, 2y) \to x", (0, 7)),
+        (r"\map(\sin, \limit(x, x))", (11, 23)),
+        (r"\sin^{-1} x", (5, 9)),
+    ];
+
+    let mut env = Environment::default();
+    for (input, (start, end)) in tests {
+        let l = Lexer::new(input);
+        let mut p = Parser::new(l);
+        let stmt = p.parse_stmt().unwrap();
+        let e = eval_stmt(stmt, &mut env).unwrap_err();
+        assert_eq!(e.span, Some(Span::new(start, end)));
+    }
+}
+
+#[test]
+fn test_eval_error_kind() {
+    let tests = [
+        ("1/0", ErrorKind::Domain, "division by zero"),
+        ("2^40", ErrorKind::Overflow, "integer overflow"),
+        ("99999999999", ErrorKind::Overflow, "number too large"),
+        ("2^30 x + 2^30 x", ErrorKind::Overflow, "integer overflow"),
+        (
+            r"\sum(k, k, 1, 100000)",
+            ErrorKind::Overflow,
+            "integer overflow",
+        ),
+        (
+            "(1, 2) + (1, 2, 3)",
+            ErrorKind::TypeMismatch,
+            r"unsupported operand: +: \R^2 x \R^3 -> ?",
+        ),
+        (
+            r"\infty - \infty",
+            ErrorKind::Undefined,
+            "indeterminate form",
+        ),
+        (r"\exp(1, 2)", ErrorKind::Arity, "arguments error"),
+        ("x = 1 = 2", ErrorKind::TypeMismatch, "unsupported operand"),
+        (r"\sin^2", ErrorKind::TypeMismatch, "unsupported operand"),
+        (
+            r"\sin^{-1} x",
+            ErrorKind::Domain,
+            "power of a function must be a positive integer",
+        ),
+        (
+            r"\sin^{1/2}(x)",
+            ErrorKind::Domain,
+            "power of a function must be a positive integer",
+        ),
+        (r"2^\sin x", ErrorKind::TypeMismatch, "unsupported operand"),
+        (
+            r"\map(\ln, (1, 0))",
+            ErrorKind::Domain,
+            r"\map: logarithm of zero",
+        ),
+    ];
+
+    let mut env = Environment::default();
+    for (input, kind, message) in tests {
+        let l = Lexer::new(input);
+        let mut p = Parser::new(l);
+        let stmt = p.parse_stmt().unwrap();
+        let e = eval_stmt(stmt, &mut env).unwrap_err();
+        assert_eq!((e.kind, e.to_string()), (kind, message.to_string()));
+    }
+}
+
+#[test]
+fn test_eval_error_source() {
+    use std::error::Error;
+
+    let mut env = Environment::default();
+    let l = Lexer::new(r"\map(x \to 1/x, (1, 0))");
+    let mut p = Parser::new(l);
+    let stmt = p.parse_stmt().unwrap();
+    let e = eval_stmt(stmt, &mut env).unwrap_err();
+    assert_eq!(e.message, r"\map");
+    let source = e.source().unwrap();
+    assert_eq!(source.to_string(), "division by zero");
+    assert!(source.source().is_none());
+}
@@ -0,0 +1,112 @@
+use super::ast::StmtKind;
+use super::environment::Environment;
+use super::evaluator;
+use super::lexer::Lexer;
+use super::parser::Parser;
+use crate::math::builtin::Builtin;
+use crate::math::error::EvalError;
+use crate::math::expr::Expr;
+use crate::math::num::Num;
+use std::ops::RangeInclusive;
+
+#[cfg(test)]
+mod test;
+
+/// An evaluated statement and its result.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub input: String,
+    pub output: Expr,
+}
+
+/// Return the index and the entry numbered by the argument of `\out` or
+/// `\in`.
+fn entry<'a>(
+    name: &str,
+    args: &[Expr],
+    env: &'a Environment,
+) -> Result<(usize, &'a Entry), EvalError> {
+    let n = match args {
+        [Expr::Num(Num::Int(n))] => *n,
+        _ => return Err(EvalError::type_mismatch("expected an integer")),
+    };
+    env.entry(n)
+        .ok_or_else(|| EvalError::domain(format!("no entry {}({})", name, n)))
+}
+
+/// The result of an earlier statement.
+pub struct Out;
+
+impl Builtin for Out {
+    fn name(&self) -> &'static str {
+        "\\out"
+    }
+
+    fn arity(&self) -> RangeInclusive<usize> {
+        1..=1
+    }
+
+    fn help(&self) -> &'static str {
+        "\\out(n): result n, or the n-th last result if n < 0"
+    }
+
+    fn eval(
+        &self,
+        args: Vec<Expr>,
+        env: &Environment,
+    ) -> Result<Expr, EvalError> {
+        Ok(entry(self.name(), &args, env)?.1.output.clone())
+    }
+}
+
+/// An earlier input evaluated again.
+pub struct In;
+
+impl Builtin for In {
+    fn name(&self) -> &'static str {
+        "\\in"
+    }
+
+    fn arity(&self) -> RangeInclusive<usize> {
+        1..=1
+    }
+
+    fn help(&self) -> &'static str {
+        "\\in(n): input n evaluated again, or the n-th last if n < 0"
+    }
+
+    fn eval(
+        &self,
+        args: Vec<Expr>,
+        env: &Environment,
+    ) -> Result<Expr, EvalError> {
+        let (i, e) = entry(self.name(), &args, env)?;
+        let input = &e.input;
+        // References in the input are relative to the time it was entered.
+        let mut scope = env.clone();
+        scope.truncate_history(i);
+        match scope.history().last() {
+            Some(last) => scope.set("\\ans", last.output.clone()),
+            None => {
+                scope.unset("\\ans");
+            }
+        }
+        let l = Lexer::new(input).with_names(env.names().clone());
+        match Parser::new(l).parse_stmt().map(|s| s.kind) {
+            Ok(StmtKind::Expr(e)) => {
+                evaluator::eval_expr(e, &scope).map_err(|mut e| {
+                    // The span points into the earlier input.
+                    e.span = None;
+                    e.context(self.name())
+                })
+            }
+            _ => Err(EvalError::type_mismatch(format!(
+                "{} is not an expression",
+                input
+            ))),
+        }
+    }
+}
+
+pub static OUT: Out = Out;
+pub static IN: In = In;
@@ -0,0 +1,79 @@
+use super::super::script;
+use super::*;
+
+#[test]
+fn test_history() {
+    let tests = [
+        ("x^2 + 1", "((x ^ 2) + 1)"),
+        (r"\diff(\ans, x)", "(2 * x)"),
+        (r"\let y = 3", r"\let y = 3"),
+        (r"\out(1) + \out(-1)", "((x ^ 2) + 4)"),
+        (r"\out(2)", "(2 * x)"),
+        (r"\let x = 2", r"\let x = 2"),
+        (r"\in(1)", "5"),
+        (r"\in(-1)", "5"),
+        (r"\in(8)", "5"),
+        (r"\out(10)", r"no entry \out(10)"),
+        (r"\out(0)", r"no entry \out(0)"),
+        (r"\out(z)", "expected an integer"),
+        (r"\in(3)", r"\let y = 3 is not an expression"),
+        (r"\let x = 1; 1/x", "1"),
+        (r"\let x = 0", r"\let x = 0"),
+        (r"\in(-2)", r"\in: division by zero"),
+    ];
+
+    let mut env = Environment::default();
+    for (input, expected) in tests {
+        let mut out = Vec::new();
+        let r = script::run(input, &mut env, &mut |s| out.push(s));
+        let out = match r {
+            Ok(()) => out.join("\n"),
+            Err(e) => e.message.lines().last().unwrap().to_string(),
+        };
+        assert!(out.ends_with(expected), "{}: {}", input, out);
+    }
+    assert_eq!(env.history().len(), 12);
+    assert_eq!(env.history()[3].input, r"\out(1) + \out(-1)");
+}
+
+#[test]
+fn test_history_ans() {
+    let tests = [
+        (r"\ans + 1", r"(\ans + 1)"),
+        ("1", "1"),
+        (r"\ans + 1", "2"),
+        ("5", "5"),
+        (r"\in(3)", "2"),
+        (r"\in(1)", r"(\ans + 1)"),
+    ];
+
+    let mut env = Environment::default();
+    for (input, expected) in tests {
+        let mut out = Vec::new();
+        script::run(input, &mut env, &mut |s| out.push(s)).unwrap();
+        assert_eq!(out, [expected], "{}", input);
+    }
+}
+
+#[test]
+fn test_history_higher_order() {
+    let tests = [
+        ("x^2", "(x ^ 2)"),
+        ("2 x", "(2 * x)"),
+        (r"\map(\out, (1, 2))", "((x ^ 2), (2 * x))"),
+        (r"\apply(\out, 1)", "(x ^ 2)"),
+        (r"\nest(\out, 2, 1)", "(2 * x)"),
+        (r"\compose(y \to y + 1, \out)(2)", "((2 * x) + 1)"),
+        (r"\let o(n) = \out(n) + 1", r"\let o(n) = (\out(n) + 1)"),
+        ("o(2)", "((2 * x) + 1)"),
+        (r"\map(n \to \out(n), (1, 2))", "((x ^ 2), (2 * x))"),
+        (r"\nest(n \to \out(n), 2, 1)", "(2 * x)"),
+    ];
+
+    let mut env = Environment::default();
+    for (input, expected) in tests {
+        let mut out = Vec::new();
+        script::run(input, &mut env, &mut |s| out.push(s)).unwrap();
+        assert_eq!(out, [expected], "{}", input);
+    }
+}
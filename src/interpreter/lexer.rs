@@ -1,13 +1,45 @@
-use super::token::Token;
+use super::token::{Span, Token};
+use std::collections::BTreeSet;
 use std::iter::Peekable;
 use std::str::CharIndices;
 
 #[cfg(test)]
 mod test;
 
+/// Identifiers of more than one letter known to the lexer.
+#[derive(Clone, Default)]
+pub struct Names {
+    /// Read every run of letters and digits as one identifier.
+    pub multi_letter: bool,
+    declared: BTreeSet<String>,
+}
+
+impl Names {
+    pub fn declare(&mut self, name: &str) {
+        self.declared.insert(name.to_string());
+    }
+
+    /// Return the declared names in order.
+    pub fn declared(&self) -> impl Iterator<Item = &str> {
+        self.declared.iter().map(String::as_str)
+    }
+
+    /// Return the longest declared name at the beginning of `s`.
+    fn longest_prefix(&self, s: &str) -> Option<usize> {
+        self.declared
+            .iter()
+            .filter(|n| s.starts_with(n.as_str()))
+            .map(|n| n.len())
+            .max()
+    }
+}
+
 pub struct Lexer<'input> {
     input: &'input str,
     chars: Peekable<CharIndices<'input>>,
+    names: Names,
+    /// Whether the rest of the line is a `\var` declaration.
+    declaring: bool,
 }
 
 impl<'input> Lexer<'input> {
@@ -15,36 +47,92 @@ impl<'input> Lexer<'input> {
         Lexer {
             input,
             chars: input.char_indices().peekable(),
+            names: Names::default(),
+            declaring: false,
         }
     }
 
-    pub fn next_token(&mut self) -> Token<'input> {
+    pub fn with_names(mut self, names: Names) -> Lexer<'input> {
+        self.names = names;
+        self
+    }
+
+    pub fn declare(&mut self, name: &str) {
+        self.names.declare(name);
+    }
+
+    pub fn input(&self) -> &'input str {
+        self.input
+    }
+
+    /// Return the next token and its byte range. Comments from `#` to the
+    /// end of the line are skipped like white space, and so are `\left` and
+    /// `\right` before a delimiter as in `\left(x\right)`.
+    pub fn next_token(&mut self) -> (Token<'input>, Span) {
         loop {
-            match self.chars.next() {
-                None => return Token::Eof,
-                Some((i, c)) => match c {
-                    ' ' | '\t' => continue,
-                    '\n' => return Token::LF,
-                    '+' => return Token::Plus,
-                    '-' => return Token::Minus,
-                    '*' => return Token::Star,
-                    '/' => return Token::Slash,
-                    '^' => return Token::Caret,
-                    '(' => return Token::Lparen,
-                    ')' => return Token::Rparen,
-                    '=' => return Token::Eq,
-                    ',' => return Token::Comma,
-                    '0'..='9' => return self.read_number(i),
-                    '\\' => return self.read_word(i),
-                    'a'..='z' | 'A'..='Z' => {
-                        return Token::Ident(&self.input[i..i + 1])
-                    }
-                    _ => return Token::Illegal,
-                },
+            let mut comment = false;
+            while let Some(&(_, c)) = self.chars.peek() {
+                match c {
+                    '\n' => break,
+                    '#' => comment = true,
+                    ' ' | '\t' => {}
+                    _ if !comment => break,
+                    _ => {}
+                }
+                self.chars.next();
+            }
+            let start = self.pos();
+            match self.read_token() {
+                Token::Ident("\\left") | Token::Ident("\\right") => {}
+                tok => return (tok, Span::new(start, self.pos())),
             }
         }
     }
 
+    /// Return the offset of the next character.
+    fn pos(&mut self) -> usize {
+        self.chars.peek().map_or(self.input.len(), |&(i, _)| i)
+    }
+
+    fn read_token(&mut self) -> Token<'input> {
+        match self.chars.next() {
+            None => Token::Eof,
+            Some((i, c)) => match c {
+                '\n' => {
+                    self.declaring = false;
+                    Token::LF
+                }
+                ';' => {
+                    self.declaring = false;
+                    Token::Semicolon
+                }
+                '+' => Token::Plus,
+                '-' => Token::Minus,
+                '*' => Token::Star,
+                '/' => Token::Slash,
+                '^' => Token::Caret,
+                '(' => Token::Lparen,
+                ')' => Token::Rparen,
+                '{' => Token::Lbrace,
+                '}' => Token::Rbrace,
+                '[' => Token::Lbracket,
+                ']' => Token::Rbracket,
+                '=' => Token::Eq,
+                ',' => Token::Comma,
+                '0'..='9' => self.read_number(i),
+                '\\' => {
+                    let tok = self.read_word(i);
+                    if tok == Token::Var {
+                        self.declaring = true;
+                    }
+                    tok
+                }
+                c if c.is_alphabetic() => self.read_ident(i, c),
+                _ => Token::Illegal,
+            },
+        }
+    }
+
     fn read_number(&mut self, pos: usize) -> Token<'input> {
         loop {
             match self.chars.peek() {
@@ -60,6 +148,51 @@ impl<'input> Lexer<'input> {
         }
     }
 
+    /// Read a single letter, a declared name or, in multi-letter mode and
+    /// in declarations, a run of letters and digits, followed by an
+    /// optional subscript such as `_1` or `_{max}`.
+    fn read_ident(&mut self, pos: usize, c: char) -> Token<'input> {
+        let mut end = pos + c.len_utf8();
+        if self.names.multi_letter || self.declaring {
+            end = self.skip_while(char::is_alphanumeric).unwrap_or(end);
+        } else if let Some(n) = self.names.longest_prefix(&self.input[pos..]) {
+            end = self.skip_to(pos + n);
+        }
+        let rest = &self.input[end..];
+        if rest.starts_with("_{") {
+            if let Some(n) = rest.find('}') {
+                end = self.skip_to(end + n + 1);
+            }
+        } else if rest.starts_with('_')
+            && rest[1..].starts_with(char::is_alphanumeric)
+        {
+            self.chars.next();
+            end = self.skip_while(char::is_alphanumeric).unwrap_or(end);
+        }
+        Token::Ident(&self.input[pos..end])
+    }
+
+    /// Consume characters before `end` and return `end`.
+    fn skip_to(&mut self, end: usize) -> usize {
+        while self.chars.peek().is_some_and(|&(j, _)| j < end) {
+            self.chars.next();
+        }
+        end
+    }
+
+    /// Consume characters satisfying `f` and return the end of the last one.
+    fn skip_while(&mut self, f: fn(char) -> bool) -> Option<usize> {
+        let mut end = None;
+        while let Some(&(j, c)) = self.chars.peek() {
+            if !f(c) {
+                break;
+            }
+            self.chars.next();
+            end = Some(j + c.len_utf8());
+        }
+        end
+    }
+
     fn read_word(&mut self, pos: usize) -> Token<'input> {
         loop {
             match self.chars.peek() {
@@ -77,10 +210,14 @@ impl<'input> Lexer<'input> {
     }
 
     fn word_to_token(s: &'input str) -> Token<'input> {
-        if s == "\\let" {
-            Token::Let
-        } else {
-            Token::Ident(s)
+        match s {
+            "\\let" => Token::Let,
+            "\\to" => Token::To,
+            "\\var" => Token::Var,
+            "\\frac" => Token::Frac,
+            "\\sqrt" => Token::Sqrt,
+            "\\cdot" => Token::Star,
+            _ => Token::Ident(s),
         }
     }
 }
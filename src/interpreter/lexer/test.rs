@@ -88,13 +88,51 @@ fn test_next_token() {
                 Token::Rparen,
             ],
         ),
+        (
+            r"x \to \tan x",
+            vec![
+                Token::Ident("x"),
+                Token::To,
+                Token::Ident("\\tan"),
+                Token::Ident("x"),
+            ],
+        ),
+        (
+            r"\frac{1}{\left(x\right)} \cdot \sqrt[3]",
+            vec![
+                Token::Frac,
+                Token::Lbrace,
+                Token::Num("1"),
+                Token::Rbrace,
+                Token::Lbrace,
+                Token::Lparen,
+                Token::Ident("x"),
+                Token::Rparen,
+                Token::Rbrace,
+                Token::Star,
+                Token::Sqrt,
+                Token::Lbracket,
+                Token::Num("3"),
+                Token::Rbracket,
+            ],
+        ),
+        (
+            "x; y # z ; w\n1",
+            vec![
+                Token::Ident("x"),
+                Token::Semicolon,
+                Token::Ident("y"),
+                Token::LF,
+                Token::Num("1"),
+            ],
+        ),
     ];
 
     for (input, expected) in tests {
         let mut l = Lexer::new(input);
         let mut e = expected.into_iter();
         loop {
-            let tok = l.next_token();
+            let (tok, _) = l.next_token();
             if tok == Token::Eof {
                 break;
             }
@@ -102,3 +140,102 @@ fn test_next_token() {
         }
     }
 }
+
+#[test]
+fn test_read_ident() {
+    let mut declared = Names::default();
+    declared.declare("theta");
+    let multi_letter = Names {
+        multi_letter: true,
+        ..Names::default()
+    };
+
+    let tests = [
+        (
+            "x_1 x_{max}y_",
+            Names::default(),
+            vec![
+                Token::Ident("x_1"),
+                Token::Ident("x_{max}"),
+                Token::Ident("y"),
+                Token::Illegal,
+            ],
+        ),
+        (
+            "2θα",
+            Names::default(),
+            vec![Token::Num("2"), Token::Ident("θ"), Token::Ident("α")],
+        ),
+        (
+            "thetax_0 tea",
+            declared,
+            vec![
+                Token::Ident("theta"),
+                Token::Ident("x_0"),
+                Token::Ident("t"),
+                Token::Ident("e"),
+                Token::Ident("a"),
+            ],
+        ),
+        (
+            "v0 velocity_{max}*t",
+            multi_letter,
+            vec![
+                Token::Ident("v0"),
+                Token::Ident("velocity_{max}"),
+                Token::Star,
+                Token::Ident("t"),
+            ],
+        ),
+        (
+            "\\var ab, c\nab",
+            Names::default(),
+            vec![
+                Token::Var,
+                Token::Ident("ab"),
+                Token::Comma,
+                Token::Ident("c"),
+                Token::LF,
+                Token::Ident("a"),
+                Token::Ident("b"),
+            ],
+        ),
+    ];
+
+    for (input, names, expected) in tests {
+        let mut l = Lexer::new(input).with_names(names);
+        let mut e = expected.into_iter();
+        loop {
+            let (tok, _) = l.next_token();
+            if tok == Token::Eof {
+                break;
+            }
+            assert_eq!(Some(tok), e.next());
+        }
+        assert_eq!(e.next(), None);
+    }
+}
+
+#[test]
+fn test_token_span() {
+    let tests = [
+        ("12 + x_{1}", vec![(0, 2), (3, 4), (5, 10)]),
+        ("\\sin(θ)\n", vec![(0, 4), (4, 5), (5, 7), (7, 8), (8, 9)]),
+        ("  ", vec![]),
+    ];
+
+    for (input, expected) in tests {
+        let mut l = Lexer::new(input);
+        let mut e = expected.into_iter();
+        loop {
+            let (tok, span) = l.next_token();
+            if tok == Token::Eof {
+                assert_eq!(span, Span::new(input.len(), input.len()));
+                break;
+            }
+            let (start, end) = e.next().unwrap();
+            assert_eq!(span, Span::new(start, end));
+        }
+        assert_eq!(e.next(), None);
+    }
+}
@@ -1,4 +1,7 @@
-use super::{ast, lexer::Lexer, token::Token};
+use super::ast::{self, Command, ExprKind, StmtKind};
+use super::lexer::Lexer;
+use super::token::{Span, Token};
+use std::fmt;
 
 #[cfg(test)]
 mod test;
@@ -6,6 +9,7 @@ mod test;
 #[derive(PartialEq, PartialOrd)]
 enum Precedence {
     Lowest,
+    Lambda,
     Eq,
     Sum,
     Prod,
@@ -15,9 +19,54 @@ enum Precedence {
     LPow,
 }
 
+/// An unexpected or illegal token.
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub span: Span,
+    /// Text of the token, empty at the end of input.
+    pub found: String,
+    /// Descriptions of the tokens allowed here, empty if the token is illegal.
+    pub expected: Vec<&'static str>,
+}
+
+impl ParseError {
+    /// Return the line of `input` containing the error underlined by carets
+    /// followed by the message.
+    pub fn render(&self, input: &str) -> String {
+        format!("{} {}", self.span.underline(input), self)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.expected.is_empty() {
+            return write!(f, "illegal character `{}`", self.found);
+        }
+        let n = self.expected.len();
+        write!(f, "expected ")?;
+        for (i, e) in self.expected.iter().enumerate() {
+            match i {
+                0 => write!(f, "{}", e)?,
+                _ if i + 1 == n => write!(f, " or {}", e)?,
+                _ => write!(f, ", {}", e)?,
+            }
+        }
+        if self.found.is_empty() {
+            write!(f, ", found end of input")
+        } else {
+            write!(f, ", found `{}`", self.found)
+        }
+    }
+}
+
 pub struct Parser<'input> {
     lexer: Lexer<'input>,
     token: Token<'input>,
+    span: Span,
+    /// Span of the previous token.
+    prev: Span,
+    /// Number of open parentheses.
+    depth: usize,
 }
 
 impl<'input> Parser<'input> {
@@ -25,76 +74,305 @@ impl<'input> Parser<'input> {
         let mut p = Parser {
             lexer,
             token: Token::Eof,
+            span: Span::default(),
+            prev: Span::default(),
+            depth: 0,
         };
 
         p.bump();
         p
     }
 
+    /// Move to the next token. Line breaks inside parentheses, braces and
+    /// brackets are skipped.
     fn bump(&mut self) {
-        self.token = self.lexer.next_token();
+        match self.token {
+            Token::Lparen | Token::Lbrace | Token::Lbracket => self.depth += 1,
+            Token::Rparen | Token::Rbrace | Token::Rbracket => {
+                self.depth = self.depth.saturating_sub(1)
+            }
+            _ => {}
+        }
+        self.prev = self.span;
+        loop {
+            let (token, span) = self.lexer.next_token();
+            self.token = token;
+            self.span = span;
+            if self.token != Token::LF || self.depth == 0 {
+                break;
+            }
+        }
+    }
+
+    /// Move past an operator and the line breaks after it, which continue
+    /// the statement on the next line.
+    fn bump_operator(&mut self) {
+        self.bump();
+        while self.token == Token::LF {
+            self.bump();
+        }
+    }
+
+    /// Return the span from `start` to the end of the previous token.
+    fn span_from(&self, start: Span) -> Span {
+        start.to(self.prev)
+    }
+
+    /// Return an error at the current token.
+    fn error(&self, expected: &[&'static str]) -> ParseError {
+        let (found, expected) = match self.token {
+            Token::Eof | Token::LF => (String::new(), expected.to_vec()),
+            Token::Illegal => (self.text(), Vec::new()),
+            _ => (self.text(), expected.to_vec()),
+        };
+        ParseError {
+            span: self.span,
+            found,
+            expected,
+        }
+    }
+
+    fn text(&self) -> String {
+        self.lexer.input()[self.span.start..self.span.end].to_string()
     }
 
-    pub fn parse_stmt(&mut self) -> ast::Stmt<'input> {
+    /// Parse the next non-empty statement of a script, or return `None` at
+    /// the end of input.
+    pub fn next_stmt(
+        &mut self,
+    ) -> Option<Result<ast::Stmt<'input>, ParseError>> {
+        while let Token::LF | Token::Semicolon = self.token {
+            self.bump();
+        }
         match self.token {
-            Token::Eof | Token::LF => ast::Stmt::Empty,
-            Token::Let => self.parse_let_stmt(),
-            _ => self.parse_expr_stmt(),
+            Token::Eof => None,
+            _ => Some(self.parse_stmt()),
         }
     }
 
-    fn parse_let_stmt(&mut self) -> ast::Stmt<'input> {
+    /// Return whether the last statement was terminated by `;`.
+    pub fn at_semicolon(&self) -> bool {
+        self.token == Token::Semicolon
+    }
+
+    pub fn parse_stmt(&mut self) -> Result<ast::Stmt<'input>, ParseError> {
+        let start = self.span;
+        let kind = match self.token {
+            Token::Eof | Token::LF | Token::Semicolon => StmtKind::Empty,
+            Token::Let => self.parse_let_stmt()?,
+            Token::Var => self.parse_var_stmt()?,
+            Token::Ident(s) if Parser::is_command(s) => {
+                self.parse_command_stmt(s)?
+            }
+            _ => self.parse_expr_stmt()?,
+        };
+        let span = match kind {
+            StmtKind::Empty => start,
+            _ => self.span_from(start),
+        };
+        match self.token {
+            Token::Eof | Token::LF | Token::Semicolon => {
+                let source = &self.lexer.input()[span.start..span.end];
+                Ok(ast::Stmt { kind, span, source })
+            }
+            _ => Err(self.error(&["operator", "end of input"])),
+        }
+    }
+
+    fn parse_let_stmt(&mut self) -> Result<StmtKind<'input>, ParseError> {
         self.bump();
-        let lhs = self.parse_expr(Precedence::Eq);
+        let lhs = self.parse_expr(Precedence::Eq)?;
         if self.token != Token::Eq {
-            panic!("parsing error");
+            return Err(self.error(&["`=`"]));
         }
+        self.bump_operator();
+        let rhs = self.parse_expr(Precedence::Lowest)?;
+        Ok(StmtKind::Let(lhs, rhs))
+    }
+
+    fn parse_var_stmt(&mut self) -> Result<StmtKind<'input>, ParseError> {
         self.bump();
-        let rhs = self.parse_expr(Precedence::Lowest);
-        ast::Stmt::Let(lhs, rhs)
+        let v = self.parse_names()?;
+        // Later statements of the same input use the names.
+        for s in &v {
+            self.lexer.declare(s);
+        }
+        Ok(StmtKind::Var(v))
+    }
+
+    /// Return whether `s` begins a command statement.
+    fn is_command(s: &str) -> bool {
+        matches!(
+            s,
+            "\\vars"
+                | "\\unset"
+                | "\\clear"
+                | "\\help"
+                | "\\type"
+                | "\\pretty"
+                | "\\tex"
+                | "\\format"
+                | "\\reset"
+                | "\\save"
+                | "\\load"
+        )
+    }
+
+    fn parse_command_stmt(
+        &mut self,
+        name: &'input str,
+    ) -> Result<StmtKind<'input>, ParseError> {
+        self.bump();
+        let cmd = match name {
+            "\\vars" => Command::Vars,
+            "\\clear" => Command::Clear,
+            "\\reset" => Command::Reset,
+            "\\unset" => Command::Unset(self.parse_names()?),
+            "\\save" => {
+                let path = self.parse_word("file name")?;
+                let history = self.token == Token::Ident("\\history");
+                if history {
+                    self.bump();
+                }
+                Command::Save(path, history)
+            }
+            "\\load" => Command::Load(self.parse_word("file name")?),
+            "\\format" => match self.token {
+                Token::Eof | Token::LF | Token::Semicolon => {
+                    Command::Format(None)
+                }
+                _ => Command::Format(Some(self.parse_word("format name")?)),
+            },
+            "\\help" => match self.token {
+                Token::Ident(s) => {
+                    self.bump();
+                    Command::Help(Some(s))
+                }
+                _ => Command::Help(None),
+            },
+            "\\pretty" => {
+                let unicode = self.token == Token::Ident("\\unicode");
+                if unicode {
+                    self.bump();
+                }
+                Command::Pretty(self.parse_expr(Precedence::Lowest)?, unicode)
+            }
+            "\\tex" => Command::Tex(self.parse_expr(Precedence::Lowest)?),
+            _ => Command::Type(self.parse_expr(Precedence::Lowest)?),
+        };
+        Ok(StmtKind::Command(cmd))
     }
 
-    fn parse_expr_stmt(&mut self) -> ast::Stmt<'input> {
-        ast::Stmt::Expr(self.parse_expr(Precedence::Lowest))
+    /// Return the text of the following tokens up to white space or the
+    /// end of the statement, such as a file name.
+    fn parse_word(
+        &mut self,
+        expected: &'static str,
+    ) -> Result<&'input str, ParseError> {
+        let start = self.span;
+        loop {
+            match self.token {
+                Token::Eof | Token::LF | Token::Semicolon => break,
+                _ if self.span != start && self.span.start > self.prev.end => {
+                    break
+                }
+                _ => self.bump(),
+            }
+        }
+        if self.span == start {
+            return Err(self.error(&[expected]));
+        }
+        let span = self.span_from(start);
+        Ok(&self.lexer.input()[span.start..span.end])
     }
 
-    fn parse_expr(&mut self, precedence: Precedence) -> ast::Expr<'input> {
+    /// Parse identifiers separated by optional commas up to the end of the
+    /// statement.
+    fn parse_names(&mut self) -> Result<Vec<&'input str>, ParseError> {
+        let mut v = Vec::new();
+        loop {
+            match self.token {
+                Token::Ident(s) => v.push(s),
+                Token::Comma => {}
+                Token::Eof | Token::LF | Token::Semicolon => return Ok(v),
+                _ => return Err(self.error(&["identifier"])),
+            }
+            self.bump();
+        }
+    }
+
+    fn parse_expr_stmt(&mut self) -> Result<StmtKind<'input>, ParseError> {
+        Ok(StmtKind::Expr(self.parse_expr(Precedence::Lowest)?))
+    }
+
+    fn parse_expr(
+        &mut self,
+        precedence: Precedence,
+    ) -> Result<ast::Expr<'input>, ParseError> {
+        let start = self.span;
         let mut left = match self.token {
             Token::Num(s) => {
                 self.bump();
-                ast::Expr::Num(s)
+                ast::Expr::new(ExprKind::Num(s), start)
             }
             Token::Ident(s) => {
+                // An identifier immediately followed by `(`, or by
+                // `\left(` as in LaTeX, is a call.
+                let rest = &self.lexer.input()[self.span.end..];
+                let call = rest.starts_with('(') || rest.starts_with("\\left(");
                 self.bump();
-                ast::Expr::Ident(s)
+                if call {
+                    let args = self.parse_expr_list()?;
+                    ast::Expr::new(
+                        ExprKind::Call(s, args),
+                        self.span_from(start),
+                    )
+                } else {
+                    ast::Expr::new(ExprKind::Ident(s), start)
+                }
             }
-            Token::Lparen => self.parse_grouped_expr(),
+            Token::Lparen => self.parse_grouped_expr()?,
+            Token::Lbrace => self.parse_braced_expr()?,
+            Token::Frac => self.parse_frac_expr()?,
+            Token::Sqrt => self.parse_sqrt_expr()?,
             _ => match self.parse_unary_op() {
-                Some(op) => self.parse_unary_expr(op),
-                None => panic!("parsing error"),
+                Some(op) => self.parse_unary_expr(op)?,
+                None => return Err(self.error(&["expression"])),
             },
         };
 
         loop {
+            if self.token == Token::To {
+                if precedence >= Precedence::Lambda {
+                    break;
+                }
+                left = self.parse_lambda_expr(left)?;
+                continue;
+            }
             match self.parse_binary_op() {
                 Some((op, lp, rp)) => {
                     if precedence >= lp {
                         break;
                     }
-                    left = self.parse_binary_expr(op, left, rp);
+                    left = self.parse_binary_expr(op, left, rp)?;
                 }
                 None => match self.token {
-                    Token::Num(_) | Token::Ident(_) | Token::Lparen => {
+                    Token::Num(_)
+                    | Token::Ident(_)
+                    | Token::Lparen
+                    | Token::Lbrace
+                    | Token::Frac
+                    | Token::Sqrt => {
                         if precedence >= Precedence::ImpliedMul {
                             break;
                         }
-                        left = self.parse_implied_mul_expr(left);
+                        left = self.parse_implied_mul_expr(left)?;
                     }
-                    _ => return left,
+                    _ => break,
                 },
             }
         }
-        left
+        Ok(left)
     }
 
     fn parse_unary_op(&self) -> Option<ast::UnOp> {
@@ -104,15 +382,21 @@ impl<'input> Parser<'input> {
         }
     }
 
-    fn parse_unary_expr(&mut self, op: ast::UnOp) -> ast::Expr<'input> {
-        self.bump();
-        let e = self.parse_expr(Precedence::Unary);
-        ast::Expr::Unary(op, Box::new(e))
+    fn parse_unary_expr(
+        &mut self,
+        op: ast::UnOp,
+    ) -> Result<ast::Expr<'input>, ParseError> {
+        let start = self.span;
+        self.bump_operator();
+        let e = self.parse_expr(Precedence::Unary)?;
+        let span = start.to(e.span);
+        Ok(ast::Expr::new(ExprKind::Unary(op, Box::new(e)), span))
     }
 
     /// Parse binary operator and return it and its left and right precedence.
     fn parse_binary_op(&self) -> Option<(ast::BinOp, Precedence, Precedence)> {
         match self.token {
+            Token::Eq => Some((ast::BinOp::Eq, Precedence::Eq, Precedence::Eq)),
             Token::Plus => {
                 Some((ast::BinOp::Add, Precedence::Sum, Precedence::Sum))
             }
@@ -137,53 +421,123 @@ impl<'input> Parser<'input> {
         op: ast::BinOp,
         left: ast::Expr<'input>,
         p: Precedence,
-    ) -> ast::Expr<'input> {
+    ) -> Result<ast::Expr<'input>, ParseError> {
+        self.bump_operator();
+        let right = self.parse_expr(p)?;
+        let span = left.span.to(right.span);
+        let kind = ExprKind::Binary(op, Box::new(left), Box::new(right));
+        Ok(ast::Expr::new(kind, span))
+    }
+
+    fn parse_lambda_expr(
+        &mut self,
+        params: ast::Expr<'input>,
+    ) -> Result<ast::Expr<'input>, ParseError> {
+        self.bump_operator();
+        let body = self.parse_expr(Precedence::Lowest)?;
+        let span = params.span.to(body.span);
+        let kind = ExprKind::Lambda(Box::new(params), Box::new(body));
+        Ok(ast::Expr::new(kind, span))
+    }
+
+    /// Parse an expression enclosed in `{` and `}` as in LaTeX.
+    fn parse_braced_expr(&mut self) -> Result<ast::Expr<'input>, ParseError> {
+        if self.token != Token::Lbrace {
+            return Err(self.error(&["`{`"]));
+        }
+        let start = self.span;
+        self.bump();
+        let mut e = self.parse_expr(Precedence::Lowest)?;
+        if self.token != Token::Rbrace {
+            return Err(self.error(&["`}`"]));
+        }
         self.bump();
-        let right = self.parse_expr(p);
-        ast::Expr::Binary(op, Box::new(left), Box::new(right))
+        e.span = self.span_from(start);
+        Ok(e)
     }
 
-    fn parse_grouped_expr(&mut self) -> ast::Expr<'input> {
+    /// Parse `\frac{a}{b}` as `a / b`.
+    fn parse_frac_expr(&mut self) -> Result<ast::Expr<'input>, ParseError> {
+        let start = self.span;
         self.bump();
-        let e = self.parse_expr(Precedence::Lowest);
-        match self.token {
-            Token::Rparen => {
-                self.bump();
-                e
+        let num = self.parse_braced_expr()?;
+        let den = self.parse_braced_expr()?;
+        let kind =
+            ExprKind::Binary(ast::BinOp::Div, Box::new(num), Box::new(den));
+        Ok(ast::Expr::new(kind, self.span_from(start)))
+    }
+
+    /// Parse `\sqrt{x}` as `x ^ (1 / 2)` and `\sqrt[n]{x}` as `x ^ (1 / n)`.
+    fn parse_sqrt_expr(&mut self) -> Result<ast::Expr<'input>, ParseError> {
+        let start = self.span;
+        self.bump();
+        let index = if self.token == Token::Lbracket {
+            self.bump();
+            let n = self.parse_expr(Precedence::Lowest)?;
+            if self.token != Token::Rbracket {
+                return Err(self.error(&["`]`"]));
             }
-            Token::Comma => {
-                self.bump();
-                let mut v = vec![e];
-                loop {
-                    let e = self.parse_expr(Precedence::Lowest);
-                    match self.token {
-                        Token::Rparen => {
-                            self.bump();
-                            v.push(e);
-                            break;
-                        }
-                        Token::Comma => {
-                            self.bump();
-                            v.push(e);
-                        }
-                        _ => panic!("No corresponding right parentheses."),
-                    }
+            self.bump();
+            n
+        } else {
+            ast::Expr::new(ExprKind::Num("2"), start)
+        };
+        let radicand = self.parse_braced_expr()?;
+        let one = ast::Expr::new(ExprKind::Num("1"), start);
+        let exp =
+            ExprKind::Binary(ast::BinOp::Div, Box::new(one), Box::new(index));
+        let exp = ast::Expr::new(exp, start);
+        let kind = ExprKind::Binary(
+            ast::BinOp::Pow,
+            Box::new(radicand),
+            Box::new(exp),
+        );
+        Ok(ast::Expr::new(kind, self.span_from(start)))
+    }
+
+    fn parse_grouped_expr(&mut self) -> Result<ast::Expr<'input>, ParseError> {
+        let start = self.span;
+        let mut v = self.parse_expr_list()?;
+        let span = self.span_from(start);
+        if v.len() == 1 {
+            let mut e = v.pop().unwrap();
+            e.span = span;
+            Ok(e)
+        } else {
+            Ok(ast::Expr::new(ExprKind::List(v), span))
+        }
+    }
+
+    /// Parse comma separated expressions enclosed in parentheses.
+    fn parse_expr_list(
+        &mut self,
+    ) -> Result<Vec<ast::Expr<'input>>, ParseError> {
+        self.bump();
+        let mut v = Vec::new();
+        loop {
+            v.push(self.parse_expr(Precedence::Lowest)?);
+            match self.token {
+                Token::Rparen => {
+                    self.bump();
+                    return Ok(v);
                 }
-                ast::Expr::List(v)
+                Token::Comma => self.bump(),
+                _ => return Err(self.error(&["`,`", "`)`"])),
             }
-            _ => panic!("No corresponding right parentheses."),
         }
     }
 
     fn parse_implied_mul_expr(
         &mut self,
         left: ast::Expr<'input>,
-    ) -> ast::Expr<'input> {
-        let right = self.parse_expr(Precedence::ImpliedMul);
-        ast::Expr::Binary(
+    ) -> Result<ast::Expr<'input>, ParseError> {
+        let right = self.parse_expr(Precedence::ImpliedMul)?;
+        let span = left.span.to(right.span);
+        let kind = ExprKind::Binary(
             ast::BinOp::ImpliedMul,
             Box::new(left),
             Box::new(right),
-        )
+        );
+        Ok(ast::Expr::new(kind, span))
     }
 }
@@ -14,7 +14,7 @@ fn test_parse_binary_expr() {
     for (input, expected) in tests {
         let l = Lexer::new(input);
         let mut p = Parser::new(l);
-        let expr = p.parse_stmt();
+        let expr = p.parse_stmt().unwrap();
         assert_eq!(format!("{:?}", expr), expected);
     }
 }
@@ -26,7 +26,7 @@ fn test_parse_unary_expr() {
     for (input, expected) in tests {
         let l = Lexer::new(input);
         let mut p = Parser::new(l);
-        let expr = p.parse_stmt();
+        let expr = p.parse_stmt().unwrap();
         assert_eq!(format!("{:?}", expr), expected);
     }
 }
@@ -38,7 +38,7 @@ fn test_parse_grouped_expr() {
     for (input, expected) in tests {
         let l = Lexer::new(input);
         let mut p = Parser::new(l);
-        let expr = p.parse_stmt();
+        let expr = p.parse_stmt().unwrap();
         assert_eq!(format!("{:?}", expr), expected);
     }
 }
@@ -53,7 +53,28 @@ fn test_parse_implied_mul_expr() {
     for (input, expected) in tests {
         let l = Lexer::new(input);
         let mut p = Parser::new(l);
-        let expr = p.parse_stmt();
+        let expr = p.parse_stmt().unwrap();
+        assert_eq!(format!("{:?}", expr), expected);
+    }
+}
+
+#[test]
+fn test_parse_latex() {
+    let tests = [
+        (r"\frac{1}{x + 1}", "(1 / (x + 1))"),
+        (r"\sqrt{x}", "(x ^ (1 / 2))"),
+        (r"\sqrt[3]{x y}", "((x im y) ^ (1 / 3))"),
+        ("x^{2n}", "(x ^ (2 im n))"),
+        (r"2 \cdot \left(a + b\right)", "(2 * (a + b))"),
+        (r"\sin x^2", r"(\sin im (x ^ 2))"),
+        (r"2\frac{a}{b}\sqrt{c}", "((2 im (a / b)) im (c ^ (1 / 2)))"),
+        ("{a + b}^{c}", "((a + b) ^ c)"),
+    ];
+
+    for (input, expected) in tests {
+        let l = Lexer::new(input);
+        let mut p = Parser::new(l);
+        let expr = p.parse_stmt().unwrap();
         assert_eq!(format!("{:?}", expr), expected);
     }
 }
@@ -66,12 +87,23 @@ fn test_parse_expr() {
         ("-2x", "(- (2 im x))"),
         ("-x^2", "(- (x ^ 2))"),
         ("x^2y", "((x ^ 2) im y)"),
+        ("a = b + 1", "(a = (b + 1))"),
+        (r"\let k = a = b", r"\let (k) = ((a = b))"),
+        (r"\unset x, \ans", r"\unset x, \ans"),
+        (r"\help \diff", r"\help \diff"),
+        (r"\type 2x", r"\type (2 im x)"),
+        (r"\pretty x/2", r"\pretty (x / 2)"),
+        (r"\pretty \unicode x", r"\pretty \unicode x"),
+        (r"\tex(x, 1)", r"\tex (x, 1)"),
+        (r"\format latex", r"\format latex"),
+        (r"\format; x", r"\format"),
+        (r"\vars", r"\vars"),
     ];
 
     for (input, expected) in tests {
         let l = Lexer::new(input);
         let mut p = Parser::new(l);
-        let expr = p.parse_stmt();
+        let expr = p.parse_stmt().unwrap();
         assert_eq!(format!("{:?}", expr), expected);
     }
 }
@@ -88,7 +120,132 @@ fn test_parse_list_expr() {
     for (input, expected) in tests {
         let l = Lexer::new(input);
         let mut p = Parser::new(l);
-        let expr = p.parse_stmt();
+        let expr = p.parse_stmt().unwrap();
         assert_eq!(format!("{:?}", expr), expected);
     }
 }
+
+#[test]
+fn test_parse_lambda_expr() {
+    let tests = [
+        (r"x \to x^2", r"(x \to (x ^ 2))"),
+        (r"(x, y) \to x + y", r"((x, y) \to (x + y))"),
+        (r"x \to y \to x y", r"(x \to (y \to (x im y)))"),
+        (r"\map(x \to 2x, (1, 2))", r"\map((x \to (2 im x)), (1, 2))"),
+    ];
+
+    for (input, expected) in tests {
+        let l = Lexer::new(input);
+        let mut p = Parser::new(l);
+        let expr = p.parse_stmt().unwrap();
+        assert_eq!(format!("{:?}", expr), expected);
+    }
+}
+
+#[test]
+fn test_parse_call_expr() {
+    let tests = [
+        ("f(x)", "f(x)"),
+        ("f (x)", "(f im x)"),
+        ("f(x, g(y))", "f(x, g(y))"),
+        ("\\sin(x)^2", "(\\sin(x) ^ 2)"),
+        ("3\\diff(y, x)", "(3 im \\diff(y, x))"),
+        ("f((1, 2))", "f((1, 2))"),
+    ];
+
+    for (input, expected) in tests {
+        let l = Lexer::new(input);
+        let mut p = Parser::new(l);
+        let expr = p.parse_stmt().unwrap();
+        assert_eq!(format!("{:?}", expr), expected);
+    }
+}
+
+#[test]
+fn test_parse_error() {
+    let tests = [
+        (
+            "(x + 1",
+            Span::new(6, 6),
+            "(x + 1\n      ^ expected `,` or `)`, found end of input",
+        ),
+        ("3 $ 4", Span::new(2, 3), "3 $ 4\n  ^ illegal character `$`"),
+        (
+            r"\let x = ",
+            Span::new(9, 9),
+            "\\let x = \n         ^ expected expression, found end of input",
+        ),
+        (
+            r"\var ab 3",
+            Span::new(8, 9),
+            "\\var ab 3\n        ^ expected identifier, found `3`",
+        ),
+        (
+            "x + 12)",
+            Span::new(6, 7),
+            "x + 12)\n      ^ expected operator or end of input, found `)`",
+        ),
+        (
+            "α * \\sin(θ,, 1)",
+            Span::new(13, 14),
+            "α * \\sin(θ,, 1)\n           ^ expected expression, found `,`",
+        ),
+        (
+            r"\frac{1}2",
+            Span::new(8, 9),
+            "\\frac{1}2\n        ^ expected `{`, found `2`",
+        ),
+        (
+            r"\sqrt[3}{x}",
+            Span::new(7, 8),
+            "\\sqrt[3}{x}\n       ^ expected `]`, found `}`",
+        ),
+        (
+            "x^{2",
+            Span::new(4, 4),
+            "x^{2\n    ^ expected `}`, found end of input",
+        ),
+        (
+            "f(1,\n2 $)",
+            Span::new(7, 8),
+            "2 $)\n  ^ illegal character `$`",
+        ),
+    ];
+
+    for (input, span, expected) in tests {
+        let l = Lexer::new(input);
+        let mut p = Parser::new(l);
+        let e = p.parse_stmt().unwrap_err();
+        assert_eq!(e.span, span);
+        assert_eq!(e.render(input), expected);
+    }
+}
+
+#[test]
+fn test_parse_span() {
+    let tests = [
+        ("1 + x", (0, 5)),
+        ("  -(a + b) ", (2, 10)),
+        (r"\diff(x^2, x)y", (0, 14)),
+        (r"\let y = 3", (0, 10)),
+        ("", (0, 0)),
+    ];
+
+    for (input, (start, end)) in tests {
+        let l = Lexer::new(input);
+        let mut p = Parser::new(l);
+        let stmt = p.parse_stmt().unwrap();
+        assert_eq!(stmt.span, Span::new(start, end));
+    }
+
+    let l = Lexer::new("x^2 + 3(y - 1)");
+    let stmt = Parser::new(l).parse_stmt().unwrap();
+    if let ast::StmtKind::Expr(e) = stmt.kind {
+        if let ExprKind::Binary(_, l, r) = e.kind {
+            assert_eq!(l.span, Span::new(0, 3));
+            assert_eq!(r.span, Span::new(6, 14));
+            return;
+        }
+    }
+    panic!();
+}
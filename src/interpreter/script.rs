@@ -0,0 +1,187 @@
+use super::environment::Environment;
+use super::evaluator;
+use super::lexer::Lexer;
+use super::parser::Parser;
+use super::token::{Span, Token};
+use std::fmt;
+use std::io;
+
+#[cfg(test)]
+mod test;
+
+/// The first error in a script.
+#[derive(Debug, PartialEq)]
+pub struct ScriptError {
+    /// Number of the line containing the error, counting from 1.
+    pub line: usize,
+    /// The line with the error underlined followed by the message.
+    pub message: String,
+}
+
+impl ScriptError {
+    fn new(input: &str, span: Option<Span>, message: String) -> ScriptError {
+        ScriptError {
+            line: span.map_or(1, |s| s.line(input)),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}:\n{}", self.line, self.message)
+    }
+}
+
+/// Return `path: message` for an error reading or writing the file at
+/// `path`, without the code of the operating system.
+pub fn file_error(path: &str, e: &io::Error) -> String {
+    let message = e.to_string();
+    let message = match message.find(" (os error ") {
+        Some(i) => &message[..i],
+        None => &message,
+    };
+    format!("{}: {}", path, message)
+}
+
+/// Return whether `input` ends inside parentheses, braces or brackets or
+/// after an operator, so that the next line continues it.
+pub fn is_incomplete(input: &str, env: &Environment) -> bool {
+    let mut l = Lexer::new(input).with_names(env.names().clone());
+    let mut depth = 0;
+    let mut last = Token::Eof;
+    loop {
+        let (token, _) = l.next_token();
+        match token {
+            Token::Eof => break,
+            Token::Illegal => return false,
+            Token::Lparen | Token::Lbrace | Token::Lbracket => depth += 1,
+            Token::Rparen | Token::Rbrace | Token::Rbracket => depth -= 1,
+            // A line break after an operator continues the statement.
+            Token::LF => continue,
+            _ => {}
+        }
+        last = token;
+    }
+    depth > 0
+        || matches!(
+            last,
+            Token::Plus
+                | Token::Minus
+                | Token::Star
+                | Token::Slash
+                | Token::Caret
+                | Token::Eq
+                | Token::To
+        )
+}
+
+/// Evaluate the lines of a script read one at a time, as from a pipe, and
+/// pass the output to `print` and each error to `error`, with lines counted
+/// from the first one. A statement continues on the next line while it is
+/// incomplete and ends at a blank line as in the REPL. A line `\quit`
+/// stops the script.
+pub fn run_lines<I>(
+    lines: I,
+    env: &mut Environment,
+    print: &mut dyn FnMut(String),
+    error: &mut dyn FnMut(ScriptError),
+) -> io::Result<()>
+where
+    I: IntoIterator<Item = io::Result<String>>,
+{
+    read_lines(lines, env, print, &mut |e| {
+        error(e);
+        true
+    })
+}
+
+/// Evaluate the statements of `input` like `run_lines`, but stop at the
+/// first error.
+pub fn run(
+    input: &str,
+    env: &mut Environment,
+    print: &mut dyn FnMut(String),
+) -> Result<(), ScriptError> {
+    let mut first = None;
+    let lines = input.lines().map(|l| Ok(l.to_string()));
+    let r = read_lines(lines, env, print, &mut |e| {
+        first = Some(e);
+        false
+    });
+    // Lines of a string are read without error.
+    debug_assert!(r.is_ok());
+    first.map_or(Ok(()), Err)
+}
+
+/// Read statements from `lines` as described for `run_lines` and pass each
+/// error to `error`, which returns whether to go on.
+fn read_lines<I>(
+    lines: I,
+    env: &mut Environment,
+    print: &mut dyn FnMut(String),
+    error: &mut dyn FnMut(ScriptError) -> bool,
+) -> io::Result<()>
+where
+    I: IntoIterator<Item = io::Result<String>>,
+{
+    // The statement being read and the number of its first line.
+    let mut input = String::new();
+    let mut start = 1;
+    let mut run_input =
+        |input: &str, start: usize, env: &mut Environment| match eval_input(
+            input, env, print,
+        ) {
+            Ok(()) => true,
+            Err(e) => error(ScriptError {
+                line: start + e.line - 1,
+                message: e.message,
+            }),
+        };
+    for (i, line) in lines.into_iter().enumerate() {
+        let line = line?;
+        let blank = line.trim().is_empty();
+        if input.is_empty() {
+            if line.trim() == "\\quit" {
+                return Ok(());
+            }
+            start = i + 1;
+        } else if !blank {
+            input.push('\n');
+        }
+        input.push_str(&line);
+        if !blank && is_incomplete(&input, env) {
+            continue;
+        }
+        if !run_input(&input, start, env) {
+            return Ok(());
+        }
+        input.clear();
+    }
+    // Report the error of a statement left open at the end of the input.
+    run_input(&input, start, env);
+    Ok(())
+}
+
+/// Evaluate the statements of `input` separated by newlines or `;` and
+/// pass the output of each to `print`, except for statements terminated
+/// by `;`. Stop at the first error.
+fn eval_input(
+    input: &str,
+    env: &mut Environment,
+    print: &mut dyn FnMut(String),
+) -> Result<(), ScriptError> {
+    let l = Lexer::new(input).with_names(env.names().clone());
+    let mut p = Parser::new(l);
+    while let Some(stmt) = p.next_stmt() {
+        let stmt = stmt.map_err(|e| {
+            ScriptError::new(input, Some(e.span), e.render(input))
+        })?;
+        let out = evaluator::eval_stmt(stmt, env)
+            .map_err(|e| ScriptError::new(input, e.span, e.render(input)))?;
+        if !out.is_empty() && !p.at_semicolon() {
+            print(out);
+        }
+    }
+    Ok(())
+}
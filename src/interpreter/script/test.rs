@@ -0,0 +1,140 @@
+use super::*;
+
+#[test]
+fn test_run() {
+    let tests = [
+        ("1 + 2\n3 * 4", vec!["3", "12"]),
+        (r"\let a = 2; a^3", vec!["8"]),
+        ("\n\n# comment\nx # comment ; y\n", vec!["x"]),
+        ("\\var abc\nabc; abc^2", vec!["\\var abc", "(abc ^ 2)"]),
+        ("1;\n;;2;", vec![]),
+        ("(1,\n  2) +\n(3, 4)\n5", vec!["(4, 6)", "5"]),
+        (
+            "\\let f(x) =\n  x^2 +\n  1",
+            vec![r"\let f(x) = ((x ^ 2) + 1)"],
+        ),
+        ("1\n\\quit\n1/0", vec!["1"]),
+        ("(1,\n\\quit)", vec![r"(1, \quit)"]),
+    ];
+
+    for (input, expected) in tests {
+        let mut env = Environment::default();
+        let mut out = Vec::new();
+        run(input, &mut env, &mut |s| out.push(s)).unwrap();
+        assert_eq!(out, expected);
+    }
+}
+
+#[test]
+fn test_is_incomplete() {
+    let tests = [
+        ("1 + 2", false),
+        ("(1,", true),
+        ("f(x, (y", true),
+        ("x^2 +", true),
+        ("x \\to", true),
+        ("\\let y =", true),
+        ("x + # comment", true),
+        ("x; y *", true),
+        ("\\frac{1}{x +", true),
+        ("x)", false),
+        ("x + $", false),
+        ("1 +; 2", false),
+        ("\\let x", false),
+        ("\\sin", false),
+        ("f(x +\n  y", true),
+    ];
+
+    let env = Environment::default();
+    for (input, expected) in tests {
+        assert_eq!(is_incomplete(input, &env), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_run_lines() {
+    let tests = [
+        (
+            "f(x\n\n1+1\n2+2\n3+3",
+            vec!["2", "4", "6"],
+            vec![(1, "f(x\n   ^ expected `,` or `)`, found end of input")],
+        ),
+        (
+            "\\let x\n1+1",
+            vec!["2"],
+            vec![(1, "\\let x\n      ^ expected `=`, found end of input")],
+        ),
+        (
+            "1\n(2,\n  3) +\n(1/0, 1)\n\\quit\n4",
+            vec!["1"],
+            vec![(4, "(1/0, 1)\n ^^^ division by zero")],
+        ),
+        (
+            "x +\n\ny",
+            vec!["y"],
+            vec![(1, "x +\n   ^ expected expression, found end of input")],
+        ),
+    ];
+
+    for (input, expected, errors) in tests {
+        let mut env = Environment::default();
+        let mut out = Vec::new();
+        let mut errs = Vec::new();
+        let lines = input.lines().map(|l| Ok(l.to_string()));
+        run_lines(lines, &mut env, &mut |s| out.push(s), &mut |e| {
+            errs.push((e.line, e.message))
+        })
+        .unwrap();
+        assert_eq!(out, expected);
+        let errors: Vec<_> = errors
+            .into_iter()
+            .map(|(l, m)| (l, m.to_string()))
+            .collect();
+        assert_eq!(errs, errors);
+    }
+}
+
+#[test]
+fn test_file_error() {
+    let e = std::fs::read_to_string("missing.sc").unwrap_err();
+    assert_eq!(
+        file_error("missing.sc", &e),
+        "missing.sc: No such file or directory"
+    );
+    let e = io::Error::other("stty failed");
+    assert_eq!(file_error("x", &e), "x: stty failed");
+}
+
+#[test]
+fn test_run_error() {
+    let tests = [
+        (
+            "1\n2 +\n3)",
+            vec!["1"],
+            3,
+            "3)\n ^ expected operator or end of input, found `)`",
+        ),
+        (
+            "1; 2\n3 $ 4\n5",
+            vec!["2"],
+            2,
+            "3 $ 4\n  ^ illegal character `$`",
+        ),
+        ("# 1/0\n\n1/0", vec![], 3, "1/0\n^^^ division by zero"),
+        (
+            "1\nf(x,\n\n2",
+            vec!["1"],
+            2,
+            "f(x,\n    ^ expected expression, found end of input",
+        ),
+    ];
+
+    for (input, expected, line, message) in tests {
+        let mut env = Environment::default();
+        let mut out = Vec::new();
+        let e = run(input, &mut env, &mut |s| out.push(s)).unwrap_err();
+        assert_eq!(e.line, line);
+        assert_eq!(e.message, message);
+        assert_eq!(out, expected);
+    }
+}
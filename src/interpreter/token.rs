@@ -1,3 +1,43 @@
+/// Byte range of a token or a syntax node in the input.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    /// Return the span from the start of `self` to the end of `other`.
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start, other.end)
+    }
+
+    /// Return the number of the line of `input` where the span starts,
+    /// counting from 1.
+    pub fn line(&self, input: &str) -> usize {
+        input[..self.start].matches('\n').count() + 1
+    }
+
+    /// Return the line of `input` containing the span underlined by carets.
+    pub fn underline(&self, input: &str) -> String {
+        let start = self.start;
+        let line_start = input[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end =
+            input[start..].find('\n').map_or(input.len(), |i| start + i);
+        let col = input[line_start..start].chars().count();
+        let width = input[start..self.end.min(line_end)].chars().count();
+        format!(
+            "{}\n{}{}",
+            &input[line_start..line_end],
+            " ".repeat(col),
+            "^".repeat(width.max(1))
+        )
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Token<'a> {
     Num(&'a str),   // 16
@@ -9,9 +49,18 @@ pub enum Token<'a> {
     Caret,          // ^
     Lparen,         // (
     Rparen,         // )
+    Lbrace,         // {
+    Rbrace,         // }
+    Lbracket,       // [
+    Rbracket,       // ]
     Eq,             // =
     Comma,          // ,
+    Semicolon,      // ;
     Let,            // let
+    To,             // \to
+    Var,            // \var
+    Frac,           // \frac
+    Sqrt,           // \sqrt
     Illegal,
     LF,
     Eof,
@@ -0,0 +1,2 @@
+pub mod interpreter;
+pub mod math;
@@ -1,32 +1,157 @@
-mod interpreter;
-mod math;
+extern crate sym_calc;
 
-use interpreter::{
-    environment::Environment, evaluator, lexer::Lexer, parser::Parser,
-};
-use std::io::Write;
+mod editor;
 
-fn main() -> std::io::Result<()> {
+use editor::Editor;
+use std::io::{self, BufRead, IsTerminal};
+use std::path::PathBuf;
+use std::process;
+use sym_calc::interpreter::{environment::Environment, script};
+
+const USAGE: &str = "usage: sym_calc [--multi-letter] [-e statements | script]";
+
+fn main() -> io::Result<()> {
     let mut env = Environment::default();
+    let mut path = None;
+    let mut exprs = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--multi-letter" => env.names_mut().multi_letter = true,
+            "-e" => match args.next() {
+                Some(e) => exprs.push(e),
+                None => usage("missing argument to `-e`"),
+            },
+            a if a.starts_with('-') => {
+                usage(&format!("unknown option `{}`", a))
+            }
+            _ => path = Some(arg),
+        }
+    }
+    if !exprs.is_empty() {
+        run_exprs(&exprs, &mut env);
+        Ok(())
+    } else if let Some(path) = path {
+        run_file(&path, &mut env);
+        Ok(())
+    } else if io::stdin().is_terminal() {
+        repl(&mut env)
+    } else {
+        run_batch(&mut env)
+    }
+}
+
+fn usage(message: &str) -> ! {
+    eprintln!("{}", message);
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn print(out: String) {
+    println!("{}", out);
+}
+
+/// Run the statements given with `-e` and exit with status 1 on the first
+/// error.
+fn run_exprs(exprs: &[String], env: &mut Environment) {
+    for input in exprs {
+        if let Err(e) = script::run(input, env, &mut print) {
+            eprintln!("{}", e.message);
+            process::exit(1);
+        }
+    }
+}
+
+/// Run the script at `path` and exit with status 1 if it cannot be read or
+/// on the first error.
+fn run_file(path: &str, env: &mut Environment) {
+    let input = match std::fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("{}", script::file_error(path, &e));
+            process::exit(1);
+        }
+    };
+    if let Err(e) = script::run(&input, env, &mut print) {
+        eprintln!("{}: {}", path, e);
+        process::exit(1);
+    }
+}
+
+/// Evaluate the lines of a non-interactive standard input without prompts.
+/// Errors go to standard error and make the exit status 1.
+fn run_batch(env: &mut Environment) -> io::Result<()> {
+    let mut failed = false;
+    let lines = io::stdin().lock().lines();
+    script::run_lines(lines, env, &mut print, &mut |e| {
+        eprintln!("{}", e);
+        failed = true;
+    })?;
+    if failed {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// Words completed by the tab key besides the bound names.
+const KEYWORDS: [&str; 22] = [
+    "\\cdot",
+    "\\clear",
+    "\\format",
+    "\\frac",
+    "\\help",
+    "\\history",
+    "\\left",
+    "\\let",
+    "\\load",
+    "\\pretty",
+    "\\quit",
+    "\\reset",
+    "\\right",
+    "\\save",
+    "\\sqrt",
+    "\\tex",
+    "\\to",
+    "\\type",
+    "\\unicode",
+    "\\unset",
+    "\\var",
+    "\\vars",
+];
+
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".sym_calc_history"))
+}
+
+fn repl(env: &mut Environment) -> io::Result<()> {
+    let mut editor = Editor::new(history_path());
     loop {
-        print!(">> ");
-        std::io::stdout().flush()?;
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input)?;
-        match input.as_str() {
-            "" => {
-                println!();
-                break Ok(());
+        let words: Vec<String> = KEYWORDS
+            .iter()
+            .copied()
+            .chain(env.bound_names())
+            .map(String::from)
+            .collect();
+        let mut input = match editor.read_line(">> ", &words)? {
+            Some(input) => input,
+            None => break Ok(()),
+        };
+        // An empty line ends the statement even if it is incomplete.
+        while script::is_incomplete(&input, env) {
+            match editor.read_line(".. ", &words)? {
+                Some(line) if !line.trim().is_empty() => {
+                    input.push('\n');
+                    input.push_str(&line);
+                }
+                _ => break,
             }
-            "\\quit\n" => break Ok(()),
-            _ => {}
         }
-        let l = Lexer::new(&input);
-        let mut p = Parser::new(l);
-        let stmt = p.parse_stmt();
-        let out = evaluator::eval_stmt(stmt, &mut env);
-        if !out.is_empty() {
-            println!("{}", out);
+        editor.add_history(&input.replace('\n', " "));
+        if input.trim() == "\\quit" {
+            break Ok(());
+        }
+        if let Err(e) = script::run(&input, env, &mut print) {
+            println!("{}", e.message);
         }
     }
 }
@@ -1,5 +1,17 @@
+pub mod builtin;
 pub mod diff;
+pub mod dsolve;
+pub mod error;
 pub mod expr;
+pub mod format;
+pub mod func;
+pub mod integrate;
+pub mod lambda;
+pub mod limit;
 pub mod num;
+pub mod poly;
+pub mod series;
+pub mod sum;
+pub mod transform;
 #[cfg(test)]
 mod utl_test;
@@ -0,0 +1,186 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use super::error::EvalError;
+use super::expr::Expr;
+use super::{diff, dsolve, func, lambda, limit, series, sum, transform};
+use crate::interpreter::environment::Environment;
+
+#[cfg(test)]
+mod test;
+
+/// A command such as `\diff` which can be called from expressions.
+///
+/// Library users can implement this trait for their own commands and add
+/// them with `Environment::register`.
+pub trait Builtin {
+    /// Return the name including the leading backslash.
+    fn name(&self) -> &'static str;
+
+    /// Return the accepted numbers of arguments.
+    fn arity(&self) -> RangeInclusive<usize>;
+
+    /// Return a short description of the usage.
+    fn help(&self) -> &'static str;
+
+    /// Check the arguments before they are passed to `eval`.
+    fn validate(&self, args: &[Expr]) -> Result<(), EvalError> {
+        if self.arity().contains(&args.len()) {
+            Ok(())
+        } else {
+            Err(EvalError::arity("arguments error"))
+        }
+    }
+
+    fn eval(
+        &self,
+        args: Vec<Expr>,
+        env: &Environment,
+    ) -> Result<Expr, EvalError>;
+}
+
+/// Return the builtins registered in `Environment::default`.
+pub fn defaults() -> Vec<&'static dyn Builtin> {
+    vec![
+        &diff::DIFF,
+        &series::SERIES,
+        &limit::LIMIT,
+        &sum::SUM,
+        &sum::PROD,
+        &dsolve::DSOLVE,
+        &transform::LAPLACE,
+        &transform::INVLAPLACE,
+        &transform::FOURIER,
+        &transform::INVFOURIER,
+        &lambda::MAP,
+        &lambda::APPLY,
+        &lambda::COMPOSE,
+        &lambda::NEST,
+        &func::EXP,
+        &func::LN,
+        &func::SIN,
+        &func::COS,
+        &func::FACTORIAL,
+    ]
+}
+
+/// A builtin given by a function of its arguments.
+pub struct Function {
+    pub name: &'static str,
+    pub arity: RangeInclusive<usize>,
+    pub help: &'static str,
+    pub eval: fn(Vec<Expr>) -> Result<Expr, EvalError>,
+}
+
+impl Builtin for Function {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn arity(&self) -> RangeInclusive<usize> {
+        self.arity.clone()
+    }
+
+    fn help(&self) -> &'static str {
+        self.help
+    }
+
+    fn eval(
+        &self,
+        args: Vec<Expr>,
+        _: &Environment,
+    ) -> Result<Expr, EvalError> {
+        (self.eval)(args)
+    }
+}
+
+/// A builtin given by a function of its arguments and the environment of
+/// the call, for builtins which call the functions they are given.
+pub struct EnvFunction {
+    pub name: &'static str,
+    pub arity: RangeInclusive<usize>,
+    pub help: &'static str,
+    pub eval: fn(Vec<Expr>, &Environment) -> Result<Expr, EvalError>,
+}
+
+impl Builtin for EnvFunction {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn arity(&self) -> RangeInclusive<usize> {
+        self.arity.clone()
+    }
+
+    fn help(&self) -> &'static str {
+        self.help
+    }
+
+    fn eval(
+        &self,
+        args: Vec<Expr>,
+        env: &Environment,
+    ) -> Result<Expr, EvalError> {
+        (self.eval)(args, env)
+    }
+}
+
+/// A reference to a builtin, compared and ordered by its name.
+#[derive(Clone, Copy)]
+pub struct Cmd(pub &'static dyn Builtin);
+
+impl Cmd {
+    pub fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    /// Unpack the argument list, validate it and evaluate the builtin.
+    pub fn call(
+        &self,
+        argument: Expr,
+        env: &Environment,
+    ) -> Result<Expr, EvalError> {
+        let args = match argument {
+            Expr::Vec(v) => v,
+            a => vec![a],
+        };
+        self.call_args(args, env)
+    }
+
+    /// Validate the arguments and evaluate the builtin.
+    pub fn call_args(
+        &self,
+        args: Vec<Expr>,
+        env: &Environment,
+    ) -> Result<Expr, EvalError> {
+        self.0.validate(&args)?;
+        self.0.eval(args, env)
+    }
+}
+
+impl PartialEq for Cmd {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
+    }
+}
+
+impl Eq for Cmd {}
+
+impl PartialOrd for Cmd {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cmd {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name().cmp(other.name())
+    }
+}
+
+impl fmt::Debug for Cmd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,94 @@
+use super::super::expr::Expr;
+use super::*;
+use crate::interpreter::{evaluator, lexer::Lexer, parser::Parser};
+use std::ops::RangeInclusive;
+
+/// Return the value bound to the symbol given as the argument.
+struct Lookup;
+
+impl Builtin for Lookup {
+    fn name(&self) -> &'static str {
+        "\\lookup"
+    }
+
+    fn arity(&self) -> RangeInclusive<usize> {
+        1..=1
+    }
+
+    fn help(&self) -> &'static str {
+        "\\lookup(x): value bound to x"
+    }
+
+    fn validate(&self, args: &[Expr]) -> Result<(), EvalError> {
+        match args {
+            [Expr::Sym(_)] => Ok(()),
+            _ => Err(EvalError::type_mismatch("expected a symbol")),
+        }
+    }
+
+    fn eval(
+        &self,
+        args: Vec<Expr>,
+        env: &Environment,
+    ) -> Result<Expr, EvalError> {
+        match args.as_slice() {
+            [Expr::Sym(s)] => Ok(env.get(&format!("{}_", s))),
+            _ => unreachable!(),
+        }
+    }
+}
+
+static LOOKUP: Lookup = Lookup;
+
+fn twice(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    Ok(Expr::int(2) * args[0].clone())
+}
+
+static TWICE: Function = Function {
+    name: "\\twice",
+    arity: 1..=1,
+    help: "\\twice(x): 2 x",
+    eval: twice,
+};
+
+#[test]
+fn test_register() {
+    let mut env = Environment::default();
+    env.register(&LOOKUP);
+    env.register(&TWICE);
+    env.set("a_", Expr::int(3));
+
+    let tests = [
+        (r"\lookup(a)", "3"),
+        (r"\lookup(b)", "b_"),
+        (r"\lookup(1)", "expected a symbol"),
+        (r"\twice(x + 1)", "(2 * (x + 1))"),
+        (r"\twice(1, 2)", "arguments error"),
+        (r"\twice", r"\twice"),
+    ];
+
+    for (input, expected) in tests {
+        let l = Lexer::new(input);
+        let mut p = Parser::new(l);
+        let stmt = p.parse_stmt().unwrap();
+        let out = evaluator::eval_stmt(stmt, &mut env)
+            .unwrap_or_else(|e| e.to_string());
+        assert_eq!(out, expected);
+    }
+
+    let help: Vec<_> = env
+        .builtins()
+        .iter()
+        .filter(|b| b.name().starts_with("\\t"))
+        .map(|b| b.help())
+        .collect();
+    assert_eq!(help, ["\\twice(x): 2 x"]);
+}
+
+#[test]
+fn test_cmd_ord() {
+    let (diff, exp) = (Cmd(&diff::DIFF), Cmd(&func::EXP));
+    assert!(diff < exp);
+    assert_eq!(diff, Cmd(&diff::DIFF));
+    assert_eq!(format!("{:?}", Expr::Cmd(exp)), "\\exp");
+}
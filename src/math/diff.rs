@@ -1,54 +1,87 @@
+use super::builtin::{Cmd, Function};
+use super::error::EvalError;
 use super::expr::{Expr, ONE, ZERO};
+use super::func;
 
 #[cfg(test)]
 mod test;
 
-pub const CMD_DIFF: Expr = Expr::Cmd("\\diff", lib_diff);
+pub const DIFF: Function = Function {
+    name: "\\diff",
+    arity: 2..=2,
+    help: "\\diff(f, x): derivative of f with respect to x",
+    eval: lib_diff,
+};
+pub const CMD_DIFF: Expr = Expr::Cmd(Cmd(&DIFF));
 
 pub fn diff(expr: Expr, sym: &str) -> Expr {
+    differentiate(expr, sym, false)
+}
+
+/// Differentiate `expr` with respect to `sym` treating every other symbol
+/// as a constant.
+pub fn partial(expr: Expr, sym: &str) -> Expr {
+    differentiate(expr, sym, true)
+}
+
+fn differentiate(expr: Expr, sym: &str, partial: bool) -> Expr {
+    if partial && !expr.has_sym(sym) {
+        return ZERO;
+    }
+    let d = |e| differentiate(e, sym, partial);
     match expr {
-        Expr::Num(_) => ZERO,
+        Expr::Num(_) | Expr::Inf(_) => ZERO,
         Expr::Sym(s) if s == sym => ONE,
         // e @ Expr::Sym(_) => None,
-        Expr::Add(a) => {
-            Expr::sum(a.into_args().into_iter().map(|e| diff(e, sym)))
-        }
+        Expr::Add(a) => Expr::sum(a.into_args().into_iter().map(d)),
         Expr::Mul(m) => {
             let args = m.into_args();
-            Expr::sum((0..args.len()).into_iter().map(|i| {
+            Expr::sum((0..args.len()).map(|i| {
                 let mut args = args.clone();
-                args[i] = diff(std::mem::take(&mut args[i]), sym);
+                args[i] = d(std::mem::take(&mut args[i]));
                 Expr::prod(args)
             }))
         }
         Expr::Pow(base, exp) => {
             if let exp @ Expr::Num(_) = *exp {
-                exp.clone()
-                    * Expr::pow(*base.clone(), exp - ONE)
-                    * diff(*base, sym)
+                exp.clone() * Expr::pow(*base.clone(), exp - ONE) * d(*base)
+            } else if partial && !exp.has_sym(sym) {
+                let exp = *exp;
+                exp.clone() * Expr::pow(*base.clone(), exp - ONE) * d(*base)
+            } else if partial {
+                // d(b^e) = b^e (e' ln(b) + e b' / b)
+                let (base, exp) = (*base, *exp);
+                let log = d(exp.clone()) * func::ln(base.clone())
+                    + exp.clone() * d(base.clone()) / base.clone();
+                Expr::pow(base, exp) * log
             } else {
-                let expr = Expr::Pow(base, exp);
-                Expr::Call(
-                    Box::new(CMD_DIFF),
-                    Box::new(Expr::Vec(vec![expr, Expr::Sym(sym.to_string())])),
-                )
+                unevaluated_diff(Expr::Pow(base, exp), sym)
             }
         }
-        _ => Expr::Call(
-            Box::new(CMD_DIFF),
-            Box::new(Expr::Vec(vec![expr, Expr::Sym(sym.to_string())])),
-        ),
+        Expr::Call(f, x) => {
+            let df = match *f {
+                Expr::Cmd(c) => func::derivative(c.name(), *x.clone()),
+                _ => None,
+            };
+            match df {
+                Some(df) => df * d(*x),
+                None => unevaluated_diff(Expr::Call(f, x), sym),
+            }
+        }
+        _ => unevaluated_diff(expr, sym),
     }
 }
 
-pub fn lib_diff(expr: Expr) -> Expr {
-    match expr {
-        Expr::Vec(mut v) if v.len() == 2 => {
-            if let (Expr::Sym(s), e) = (v.pop().unwrap(), v.pop().unwrap()) {
-                return diff(e, &s);
-            }
-        }
-        _ => {}
+fn unevaluated_diff(expr: Expr, sym: &str) -> Expr {
+    Expr::unevaluated_call(
+        CMD_DIFF,
+        Expr::Vec(vec![expr, Expr::Sym(sym.to_string())]),
+    )
+}
+
+pub fn lib_diff(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    match args.as_slice() {
+        [e, Expr::Sym(s)] => Ok(diff(e.clone(), s)),
+        _ => Err(EvalError::type_mismatch("arguments error")),
     }
-    Expr::err("arguments error")
 }
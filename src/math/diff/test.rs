@@ -12,10 +12,15 @@ fn test_diff() {
             r"\diff(x^2 + xy, y)",
             r"(x + (2 * x * \diff((x, y))) + (y * \diff((x, y))))",
         ),
+        (r"\diff(\sin(x), x)", r"\cos(x)"),
+        (r"\diff(\cos(x), x)", r"(-1 * \sin(x))"),
+        (r"\diff(\exp(2x), x)", r"(2 * \exp((2 * x)))"),
+        (r"\diff(\ln(x), x)", "(x ^ -1)"),
+        (r"\diff(\sin(x^2), x)", r"(2 * x * \cos((x ^ 2)))"),
+        (r"\diff(\infty x, x)", r"\infty"),
     ];
 
     for (input, expected) in tests {
-        let expr = parse_expr(input);
-        assert_eq!(format!("{:?}", expr), expected);
+        assert_eq!(eval_str(input), expected);
     }
 }
@@ -0,0 +1,519 @@
+use super::builtin::{Cmd, Function};
+use super::diff::partial;
+use super::error::EvalError;
+use super::expr::{Expr, NEG_ONE, ONE, ZERO};
+use super::func;
+use super::integrate::integrate;
+use super::num::Num;
+use super::poly;
+
+#[cfg(test)]
+mod test;
+
+pub const DSOLVE: Function = Function {
+    name: "\\dsolve",
+    arity: 3..=5,
+    help: "\\dsolve(eq, y, x, x0, values): solution of the equation eq, or of eq = 0, for y(x) with y(x0), y'(x0), ... given by values",
+    eval: lib_dsolve,
+};
+pub const CMD_DSOLVE: Expr = Expr::Cmd(Cmd(&DSOLVE));
+
+/// Prefix of the symbols standing for `y`, `y'`, `y''`, ... while an
+/// equation is analysed. It cannot be produced by the lexer.
+const DERIV: &str = "\\_";
+
+fn deriv(k: usize) -> String {
+    format!("{}{}", DERIV, k)
+}
+
+fn constant(i: usize) -> Expr {
+    Expr::Sym(format!("C_{}", i))
+}
+
+/// Return `k` if `e` is the `k`-th derivative of `y` with respect to `x`,
+/// written as nested unevaluated `\diff` calls.
+fn derivative_order(e: &Expr, y: &str, x: &str) -> Option<usize> {
+    match e {
+        Expr::Sym(s) if s == y => Some(0),
+        Expr::Call(f, args) if f.is_cmd("\\diff") => match **args {
+            Expr::Vec(ref v) => match v.as_slice() {
+                [e, Expr::Sym(s)] if s == x => {
+                    Some(derivative_order(e, y, x)? + 1)
+                }
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Replace `y` and its derivatives by the symbols `deriv(k)` and record
+/// the highest order in `order`.
+fn replace(e: Expr, y: &str, x: &str, order: &mut usize) -> Expr {
+    if let Some(k) = derivative_order(&e, y, x) {
+        *order = (*order).max(k);
+        return Expr::Sym(deriv(k));
+    }
+    let mut r = |e| replace(e, y, x, order);
+    match e {
+        Expr::Add(a) => Expr::sum(a.into_args().into_iter().map(&mut r)),
+        Expr::Mul(m) => Expr::prod(m.into_args().into_iter().map(&mut r)),
+        Expr::Pow(b, n) => {
+            let b = r(*b);
+            Expr::pow(b, r(*n))
+        }
+        Expr::Vec(v) => Expr::Vec(v.into_iter().map(&mut r).collect()),
+        Expr::Call(f, a) => Expr::call(*f, r(*a)),
+        e => e,
+    }
+}
+
+fn has_deriv(e: &Expr, n: usize) -> bool {
+    (0..=n).any(|k| e.has_sym(&deriv(k)))
+}
+
+/// Split `e` into `a_0 y + a_1 y' + ... + a_n y^(n) + g` if it is linear
+/// in `y` and its derivatives.
+fn linear(e: &Expr, n: usize) -> Option<(Vec<Expr>, Expr)> {
+    let mut a = Vec::new();
+    let mut g = e.clone();
+    for k in 0..=n {
+        let ak = match poly::coeffs(e, &deriv(k))?.as_slice() {
+            [_, ak] => ak.clone(),
+            [_] => ZERO,
+            _ => return None,
+        };
+        g -= ak.clone() * Expr::Sym(deriv(k));
+        a.push(ak);
+    }
+    let g = g.expand();
+    if a.iter().any(|c| has_deriv(c, n)) || has_deriv(&g, n) {
+        return None;
+    }
+    Some((a, g))
+}
+
+fn sqrt(n: Num) -> Expr {
+    Expr::pow(Expr::Num(n), Expr::Num(Num::rational(1, 2)))
+}
+
+/// Solve `a_0 y + a_1 y' + ... + a_n y^(n) + g = 0` with numeric `a_k`.
+fn constant_coefficient(
+    a: &[Expr],
+    g: &Expr,
+    x: &str,
+) -> Result<Expr, EvalError> {
+    let p: Vec<Num> = a
+        .iter()
+        .map(|c| match c {
+            Expr::Num(n) => Some(n.clone()),
+            _ => None,
+        })
+        .collect::<Option<_>>()
+        .ok_or_else(|| EvalError::unsupported("cannot solve equation"))?;
+    let xs = Expr::Sym(x.to_string());
+
+    let (roots, rest) = poly::rational_roots(p);
+    let mut mult: Vec<(Num, usize)> = Vec::new();
+    for r in roots {
+        match mult.iter_mut().find(|(s, _)| *s == r) {
+            Some((_, m)) => *m += 1,
+            None => mult.push((r, 1)),
+        }
+    }
+    let mut basis = Vec::new();
+    for (r, m) in &mult {
+        for j in 0..*m {
+            let e = func::exp(Expr::Num(r.clone()) * xs.clone());
+            basis.push(Expr::pow(xs.clone(), Expr::int(j as i32)) * e);
+        }
+    }
+    match rest.as_slice() {
+        [] | [_] => {}
+        [c, b, a] => {
+            let disc =
+                b.clone() * b.clone() + Num::int(-4) * a.clone() * c.clone();
+            let (a, b) = (Expr::Num(a.clone()), Expr::Num(b.clone()));
+            let alpha = NEG_ONE * b / (Expr::int(2) * a.clone());
+            let two_a = Expr::int(2 * a_sign(&a)) * a;
+            if disc.signum() > 0 {
+                let s = sqrt(disc) / two_a;
+                for s in [s.clone(), NEG_ONE * s] {
+                    basis.push(func::exp((alpha.clone() + s) * xs.clone()));
+                }
+            } else {
+                let beta = sqrt(disc * Num::int(-1)) / two_a * xs.clone();
+                let e = func::exp(alpha * xs.clone());
+                basis.push(e.clone() * func::cos(beta.clone()));
+                basis.push(e * func::sin(beta));
+            }
+        }
+        _ => {
+            return Err(EvalError::unsupported(
+                "cannot solve characteristic equation",
+            ))
+        }
+    }
+
+    let hom = Expr::sum(
+        basis
+            .into_iter()
+            .enumerate()
+            .map(|(i, e)| constant(i + 1) * e),
+    );
+    if *g == ZERO {
+        Ok(hom)
+    } else {
+        Ok(hom + particular(a, NEG_ONE * g.clone(), x)?)
+    }
+}
+
+fn a_sign(a: &Expr) -> i32 {
+    match a {
+        Expr::Num(n) if n.signum() < 0 => -1,
+        _ => 1,
+    }
+}
+
+/// Split the expanded `e` into pairs of a numeric coefficient and a
+/// monomial.
+fn monomials(e: Expr) -> Vec<(Expr, Expr)> {
+    let terms = match e {
+        Expr::Add(a) => a.into_args(),
+        e => vec![e],
+    };
+    terms
+        .into_iter()
+        .map(|t| {
+            let factors = match t {
+                Expr::Mul(m) => m.into_args(),
+                t => vec![t],
+            };
+            let (c, m): (Vec<Expr>, Vec<Expr>) =
+                factors.into_iter().partition(|f| matches!(f, Expr::Num(_)));
+            (Expr::prod(c), Expr::prod(m))
+        })
+        .collect()
+}
+
+fn normalize(e: Expr) -> Expr {
+    func::combine_exp(e.expand())
+}
+
+/// Split a term `c P(x) exp(a x) sin(b x)` of a right-hand side into the
+/// rate `a`, the frequency `b` and the degree of `P`.
+fn term_form(t: &Expr, x: &str) -> Option<(Expr, Expr, usize)> {
+    let factors = match t {
+        Expr::Mul(m) => m.clone().into_args(),
+        t => vec![t.clone()],
+    };
+    let (mut a, mut b, mut rest) = (ZERO, ZERO, ONE);
+    for f in factors {
+        match f {
+            Expr::Call(ref g, ref u) if u.has_sym(x) => {
+                let p = poly::coeffs(u, x).filter(|p| p.len() == 2)?;
+                match g.cmd_name() {
+                    Some("\\exp") => a = p[1].clone(),
+                    Some("\\sin") | Some("\\cos")
+                        if p[0] == ZERO && b == ZERO =>
+                    {
+                        b = p[1].clone()
+                    }
+                    _ => return None,
+                }
+            }
+            f => rest *= f,
+        }
+    }
+    Some((a, b, poly::coeffs(&rest, x)?.len().max(1) - 1))
+}
+
+/// Find a particular solution of `sum_k a_k y^(k) = rhs` for a right-hand
+/// side made of terms `P(x) exp(a x) sin(b x)` and `P(x) exp(a x) cos(b x)`
+/// by undetermined coefficients.
+fn particular(a: &[Expr], rhs: Expr, x: &str) -> Result<Expr, EvalError> {
+    let err = || EvalError::unsupported("cannot find particular solution");
+    let xs = Expr::Sym(x.to_string());
+    let apply = |e: &Expr| {
+        let mut d = e.clone();
+        let mut r = ZERO;
+        for ak in a {
+            r += ak.clone() * d.clone();
+            d = partial(d, x);
+        }
+        normalize(r)
+    };
+
+    let terms = match normalize(rhs) {
+        Expr::Add(s) => s.into_args(),
+        e => vec![e],
+    };
+    let mut groups: Vec<(Expr, Expr, usize, Expr)> = Vec::new();
+    for t in terms {
+        let (r, f, d) = term_form(&t, x).ok_or_else(err)?;
+        match groups.iter_mut().find(|g| g.0 == r && g.1 == f) {
+            Some(g) => {
+                g.2 = g.2.max(d);
+                g.3 += t;
+            }
+            None => groups.push((r, f, d, t)),
+        }
+    }
+
+    let mut sol = ZERO;
+    for (r, f, d, rhs) in groups {
+        let e = func::exp(r * xs.clone());
+        let waves = if f == ZERO {
+            vec![e]
+        } else {
+            let fx = f * xs.clone();
+            vec![e.clone() * func::cos(fx.clone()), e * func::sin(fx)]
+        };
+        // Multiply the guess by x^m if it solves the homogeneous equation.
+        let s = (0..a.len()).find_map(|m| {
+            let basis: Vec<Expr> = (m..=m + d)
+                .flat_map(|j| {
+                    let p = Expr::pow(xs.clone(), Expr::int(j as i32));
+                    waves.iter().map(move |w| p.clone() * w.clone())
+                })
+                .collect();
+            let images: Vec<_> =
+                basis.iter().map(|e| monomials(apply(e))).collect();
+            let mut keys: Vec<Expr> = Vec::new();
+            let target = monomials(rhs.clone());
+            for (_, k) in images.iter().flatten().chain(&target) {
+                if !keys.contains(k) {
+                    keys.push(k.clone());
+                }
+            }
+            let coeff = |ms: &[(Expr, Expr)], k: &Expr| {
+                ms.iter().find(|m| m.1 == *k).map_or(ZERO, |m| m.0.clone())
+            };
+            let rows = keys
+                .iter()
+                .map(|k| images.iter().map(|ms| coeff(ms, k)).collect())
+                .collect();
+            let b = keys.iter().map(|k| coeff(&target, k)).collect();
+            let c = poly::solve_linear(rows, b)?;
+            Some(Expr::sum(basis.into_iter().zip(c).map(|(e, c)| c * e)))
+        });
+        sol += s.ok_or_else(err)?;
+    }
+    Ok(normalize(sol))
+}
+
+/// Solve `lhs = rhs` for `var` if `var` occurs in a single branch of
+/// `lhs`.
+fn invert(lhs: Expr, var: &str, rhs: Expr) -> Option<Expr> {
+    match lhs {
+        Expr::Sym(ref s) if s == var => Some(rhs),
+        Expr::Add(a) => {
+            let (with, without): (Vec<Expr>, Vec<Expr>) =
+                a.into_args().into_iter().partition(|e| e.has_sym(var));
+            match with.as_slice() {
+                [e] => invert(e.clone(), var, rhs - Expr::sum(without)),
+                _ => None,
+            }
+        }
+        Expr::Mul(m) => {
+            let (with, without): (Vec<Expr>, Vec<Expr>) =
+                m.into_args().into_iter().partition(|e| e.has_sym(var));
+            match with.as_slice() {
+                [e] => invert(e.clone(), var, rhs / Expr::prod(without)),
+                _ => None,
+            }
+        }
+        Expr::Pow(b, n) if !n.has_sym(var) => {
+            invert(*b, var, Expr::pow(rhs, Expr::pow(*n, NEG_ONE)))
+        }
+        Expr::Call(f, u) => match f.cmd_name() {
+            Some("\\ln") => invert(*u, var, func::exp(rhs)),
+            Some("\\exp") => invert(*u, var, func::ln(rhs)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Solve `a_1 y' + a_0 y + g = 0` with the integrating factor
+/// `exp(int a_0 / a_1 dx)`.
+fn first_order_linear(a: &[Expr], g: &Expr, x: &str) -> Option<Expr> {
+    let p = a[0].clone() / a[1].clone();
+    let q = NEG_ONE * g.clone() / a[1].clone();
+    let big_p = integrate(&p.expand(), x)?;
+    let mu = func::exp(big_p.clone());
+    let i = integrate(&func::combine_exp((mu * q).expand()), x)?;
+    let y = func::exp(NEG_ONE * big_p) * (i + constant(1));
+    Some(func::combine_exp(y.expand()))
+}
+
+/// Solve `n y' + m = 0` if `-m / n` factors into `f(x) g(y)`.
+fn separable(m: &Expr, n: &Expr, x: &str) -> Option<Expr> {
+    let y = deriv(0);
+    let factors = match NEG_ONE * m.clone() / n.clone() {
+        Expr::Mul(m) => m.into_args(),
+        f => vec![f],
+    };
+    let (gy, fx): (Vec<Expr>, Vec<Expr>) =
+        factors.into_iter().partition(|e| e.has_sym(&y));
+    if gy.iter().any(|e| e.has_sym(x)) {
+        return None;
+    }
+    let lhs = integrate(&Expr::pow(Expr::prod(gy), NEG_ONE).expand(), &y)?;
+    let rhs = integrate(&Expr::prod(fx).expand(), x)? + constant(1);
+    match invert(lhs.clone(), &y, rhs.clone()) {
+        Some(sol) => Some(sol),
+        None => Some(Expr::Eq(
+            Box::new(lhs - rhs + constant(1)),
+            Box::new(constant(1)),
+        )),
+    }
+}
+
+/// Solve `n y' + m = 0` if `m dx + n dy` is an exact differential.
+fn exact(m: &Expr, n: &Expr, x: &str) -> Option<Expr> {
+    let y = deriv(0);
+    let d = partial(m.clone(), &y) - partial(n.clone(), x);
+    if d.expand() != ZERO {
+        return None;
+    }
+    let f = integrate(&m.clone().expand(), x)?;
+    let h = (n.clone() - partial(f.clone(), &y)).expand();
+    if h.has_sym(x) {
+        return None;
+    }
+    let f = f + integrate(&h, &y)?;
+    Some(Expr::Eq(Box::new(f), Box::new(constant(1))))
+}
+
+fn first_order(e: &Expr, x: &str) -> Option<Expr> {
+    if let Some((a, g)) = linear(e, 1) {
+        if let Some(sol) = first_order_linear(&a, &g, x) {
+            return Some(sol);
+        }
+    }
+    let (m, n) = match poly::coeffs(e, &deriv(1))?.as_slice() {
+        [m, n] => (m.clone(), n.clone()),
+        _ => return None,
+    };
+    separable(&m, &n, x).or_else(|| exact(&m, &n, x))
+}
+
+/// Return the general solution of the equation `eq = 0` for the function
+/// `y` of `x` together with its order. Solutions which cannot be solved for
+/// `y` are returned as equations `F(x, y) = C_1`.
+fn general(eq: Expr, y: &str, x: &str) -> Result<(Expr, usize), EvalError> {
+    let mut n = 0;
+    let e = replace(eq, y, x, &mut n);
+    if e.has_sym(y) {
+        return Err(EvalError::unsupported("unsupported equation"));
+    }
+    if n == 0 {
+        return Err(EvalError::type_mismatch("not a differential equation"));
+    }
+    let mut sol = match linear(&e, n) {
+        Some((a, g)) => constant_coefficient(&a, &g, x),
+        None => Err(EvalError::unsupported("cannot solve equation")),
+    };
+    if n == 1 && sol.is_err() {
+        if let Some(s) = first_order(&e, x) {
+            sol = Ok(s);
+        }
+    }
+    Ok((sol?.subs(&deriv(0), &Expr::Sym(y.to_string())), n))
+}
+
+/// Determine the constants of the general solution `sol` of order `n`
+/// from the values of `y, y', ..., y^(n-1)` at `x0`.
+fn initial(
+    sol: Expr,
+    n: usize,
+    y: &str,
+    x: &str,
+    x0: &Expr,
+    values: &[Expr],
+) -> Result<Expr, EvalError> {
+    if values.len() != n {
+        return Err(EvalError::arity(format!("expected {} initial values", n)));
+    }
+    let err = || EvalError::unsupported("cannot satisfy initial conditions");
+    if let Expr::Eq(lhs, _) = sol {
+        let c = lhs.clone().subs(x, x0).subs(y, &values[0]);
+        return Ok(Expr::Eq(lhs, Box::new(c)));
+    }
+    let cs: Vec<String> = (1..=n).map(|i| format!("C_{}", i)).collect();
+    let mut rows = Vec::new();
+    let mut rhs = Vec::new();
+    let mut d = sol.clone();
+    for v in values {
+        let e = d.clone().subs(x, x0).expand();
+        let mut row = Vec::new();
+        let mut rest = e.clone();
+        for c in &cs {
+            match poly::coeffs(&e, c).as_deref() {
+                Some([_, a]) => row.push(a.clone()),
+                Some([_]) => row.push(ZERO),
+                _ if n == 1 => {
+                    let c1 = invert(e, c, v.clone()).ok_or_else(err)?;
+                    return Ok(sol.subs(c, &c1));
+                }
+                _ => return Err(err()),
+            }
+            rest = rest.subs(c, &ZERO);
+        }
+        rows.push(row);
+        rhs.push(v.clone() - rest);
+        d = partial(d, x);
+    }
+    let vals = poly::solve_linear(rows, rhs).ok_or_else(err)?;
+    let mut r = sol;
+    for (c, v) in cs.iter().zip(vals) {
+        r = r.subs(c, &v);
+    }
+    Ok(func::combine_exp(r.expand()))
+}
+
+/// Return the general solution of `eq = 0` for the function `y` of `x`.
+pub fn dsolve(eq: Expr, y: &str, x: &str) -> Result<Expr, EvalError> {
+    general(eq, y, x).map(|(sol, _)| sol)
+}
+
+/// Return the solution of `eq = 0` for the function `y` of `x` with
+/// `y^(k)(x0)` given by `values[k]`.
+pub fn dsolve_ivp(
+    eq: Expr,
+    y: &str,
+    x: &str,
+    x0: &Expr,
+    values: &[Expr],
+) -> Result<Expr, EvalError> {
+    let (sol, n) = general(eq, y, x)?;
+    initial(sol, n, y, x, x0, values)
+}
+
+/// Return `l - r` for an equation `l = r`, which is solved as `l - r = 0`.
+fn zero_form(eq: &Expr) -> Result<Expr, EvalError> {
+    match eq {
+        Expr::Eq(l, r) => (**l).clone().try_sub((**r).clone()),
+        eq => Ok(eq.clone()),
+    }
+}
+
+pub fn lib_dsolve(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    let eq = match args.first() {
+        Some(eq) => zero_form(eq)?,
+        None => return Err(EvalError::type_mismatch("arguments error")),
+    };
+    match args.as_slice() {
+        [_, Expr::Sym(y), Expr::Sym(x)] => dsolve(eq, y, x),
+        [_, Expr::Sym(y), Expr::Sym(x), x0, values] if !x0.has_sym(x) => {
+            let values = match values {
+                Expr::Vec(v) => v.clone(),
+                v => vec![v.clone()],
+            };
+            dsolve_ivp(eq, y, x, x0, &values)
+        }
+        _ => Err(EvalError::type_mismatch("arguments error")),
+    }
+}
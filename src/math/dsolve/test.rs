@@ -0,0 +1,145 @@
+use super::super::utl_test::*;
+
+#[test]
+fn test_dsolve_first_order() {
+    let tests = [
+        (r"\dsolve(\diff(y, x) - y, y, x)", "(C_1 * \\exp(x))"),
+        (
+            r"\dsolve(\diff(y, x) + y - x, y, x)",
+            "(x + (C_1 * \\exp((-1 * x))) + -1)",
+        ),
+        (
+            r"\dsolve(x * \diff(y, x) + y - x^2, y, x)",
+            "((C_1 * (x ^ -1)) + (1/3 * (x ^ 2)))",
+        ),
+        (
+            r"\dsolve(\diff(y, x) + 2 x y, y, x)",
+            "(C_1 * \\exp((-1 * (x ^ 2))))",
+        ),
+        (
+            r"\dsolve(\diff(y, x) - x y^2, y, x)",
+            "(-1 * ((C_1 + (1/2 * (x ^ 2))) ^ -1))",
+        ),
+        (
+            r"\dsolve(\diff(y, x) - \exp(y) x, y, x)",
+            "(-1 * \\ln((-1 * (C_1 + (1/2 * (x ^ 2))))))",
+        ),
+        (
+            r"\dsolve(2 x + y + (x + 2 y) * \diff(y, x), y, x)",
+            "(((x * y) + (x ^ 2) + (y ^ 2)) = C_1)",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_dsolve_constant_coefficient() {
+    let tests = [
+        (
+            r"\dsolve(\diff(\diff(y, x), x) + y, y, x)",
+            "((C_1 * \\cos(x)) + (C_2 * \\sin(x)))",
+        ),
+        (
+            r"\dsolve(\diff(\diff(y, x), x) - 3 * \diff(y, x) + 2 y, y, x)",
+            "((C_1 * \\exp(x)) + (C_2 * \\exp((2 * x))))",
+        ),
+        (
+            r"\dsolve(\diff(\diff(y, x), x) - 2 * \diff(y, x) + y, y, x)",
+            "((C_1 * \\exp(x)) + (C_2 * x * \\exp(x)))",
+        ),
+        (
+            r"\dsolve(\diff(\diff(y, x), x) + 2 * \diff(y, x) + 5 y, y, x)",
+            "((C_1 * \\cos((2 * x)) * \\exp((-1 * x))) + \
+             (C_2 * \\exp((-1 * x)) * \\sin((2 * x))))",
+        ),
+        (
+            r"\dsolve(\diff(\diff(\diff(y, x), x), x) - \diff(y, x), y, x)",
+            "(C_1 + (C_2 * \\exp(x)) + (C_3 * \\exp((-1 * x))))",
+        ),
+        (
+            r"\dsolve(\diff(\diff(y, x), x) - y - \exp(x), y, x)",
+            "((C_1 * \\exp(x)) + (C_2 * \\exp((-1 * x))) + \
+             (1/2 * x * \\exp(x)))",
+        ),
+        (
+            r"\dsolve(\diff(\diff(y, x), x) + 4 y - \sin(x), y, x)",
+            "((C_1 * \\cos((2 * x))) + (C_2 * \\sin((2 * x))) + \
+             (1/3 * \\sin(x)))",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_dsolve_initial_conditions() {
+    let tests = [
+        (r"\dsolve(\diff(y, x) - y, y, x, 0, 2)", "(2 * \\exp(x))"),
+        (
+            r"\dsolve(\diff(\diff(y, x), x) + y, y, x, 0, (0, 1))",
+            "\\sin(x)",
+        ),
+        (
+            r"\dsolve(\diff(\diff(y, x), x) - y - \exp(2 x), y, x, 0, (0, 0))",
+            "((-1/2 * \\exp(x)) + (1/6 * \\exp((-1 * x))) + \
+             (1/3 * \\exp((2 * x))))",
+        ),
+        (
+            r"\dsolve(\diff(y, x) - x y^2, y, x, 0, 1)",
+            "(-1 * (((1/2 * (x ^ 2)) + -1) ^ -1))",
+        ),
+        (
+            r"\dsolve(2 x + y + (x + 2 y) * \diff(y, x), y, x, 1, 1)",
+            "(((x * y) + (x ^ 2) + (y ^ 2)) = 3)",
+        ),
+        (
+            r"\dsolve(\diff(\diff(y, x), x) + y, y, x, 0, 1)",
+            "expected 2 initial values",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_dsolve_equation() {
+    let tests = [
+        (r"\dsolve(\diff(y, x) = y, y, x)", "(C_1 * \\exp(x))"),
+        (r"\dsolve(\diff(y(x), x) - y(x), y, x)", "(C_1 * \\exp(x))"),
+        (
+            r"\dsolve(\diff(y(x), x) = x y(x), y, x)",
+            "(C_1 * \\exp((1/2 * (x ^ 2))))",
+        ),
+        (
+            r"\dsolve(\diff(\diff(y(x), x), x) = -y(x), y, x, 0, (0, 1))",
+            "\\sin(x)",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_dsolve_error() {
+    let tests = [
+        (r"\dsolve(y - x, y, x)", "not a differential equation"),
+        (r"\dsolve(\diff(y, x) + y, 1, x)", "arguments error"),
+        (
+            r"\dsolve(\diff(\diff(y, x), x) - y * \diff(y, x), y, x)",
+            "cannot solve equation",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
@@ -0,0 +1,117 @@
+use std::error;
+use std::fmt;
+
+use crate::interpreter::token::Span;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// An operand or argument of the wrong type, such as a vector where a
+    /// scalar is expected.
+    TypeMismatch,
+    /// A wrong number of arguments.
+    Arity,
+    /// An argument outside the domain of an operation, such as `1/0`.
+    Domain,
+    /// A number which does not fit in the integer type.
+    Overflow,
+    /// A result with no value, such as `\infty - \infty`.
+    Undefined,
+    /// An input a command has no method for.
+    Unsupported,
+    /// A file which cannot be read, written or evaluated.
+    File,
+}
+
+/// An error raised while evaluating an expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvalError {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// Byte range of the subexpression which raised the error.
+    pub span: Option<Span>,
+    /// The error which caused this one.
+    pub source: Option<Box<EvalError>>,
+}
+
+impl EvalError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> EvalError {
+        EvalError {
+            kind,
+            message: message.into(),
+            span: None,
+            source: None,
+        }
+    }
+
+    pub fn type_mismatch(message: impl Into<String>) -> EvalError {
+        EvalError::new(ErrorKind::TypeMismatch, message)
+    }
+
+    pub fn arity(message: impl Into<String>) -> EvalError {
+        EvalError::new(ErrorKind::Arity, message)
+    }
+
+    pub fn domain(message: impl Into<String>) -> EvalError {
+        EvalError::new(ErrorKind::Domain, message)
+    }
+
+    pub fn overflow(message: impl Into<String>) -> EvalError {
+        EvalError::new(ErrorKind::Overflow, message)
+    }
+
+    pub fn undefined(message: impl Into<String>) -> EvalError {
+        EvalError::new(ErrorKind::Undefined, message)
+    }
+
+    pub fn unsupported(message: impl Into<String>) -> EvalError {
+        EvalError::new(ErrorKind::Unsupported, message)
+    }
+
+    pub fn file(message: impl Into<String>) -> EvalError {
+        EvalError::new(ErrorKind::File, message)
+    }
+
+    /// Point the error to `span` unless it already points to a
+    /// subexpression.
+    pub fn at(mut self, span: Span) -> EvalError {
+        self.span.get_or_insert(span);
+        self
+    }
+
+    /// Wrap the error in one with the same kind and `message` as context.
+    pub fn context(self, message: impl Into<String>) -> EvalError {
+        EvalError {
+            kind: self.kind,
+            message: message.into(),
+            span: self.span,
+            source: Some(Box::new(self)),
+        }
+    }
+
+    /// Return the line of `input` with the failed subexpression underlined
+    /// followed by the message.
+    pub fn render(&self, input: &str) -> String {
+        match self.span {
+            Some(span) => format!("{} {}", span.underline(input), self),
+            None => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        match &self.source {
+            Some(e) => write!(f, ": {}", e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl error::Error for EvalError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|e| e as &(dyn error::Error + 'static))
+    }
+}
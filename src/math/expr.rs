@@ -1,7 +1,13 @@
+use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::fmt;
 
+use super::builtin::Cmd;
+use super::error::EvalError;
+use super::func;
 use super::num::{self, Num};
+use super::series::{self, Series};
+use crate::interpreter::environment::Environment;
 
 #[cfg(test)]
 mod test;
@@ -18,9 +24,40 @@ pub enum Expr {
     Mul(Mul),
     Pow(Box<Expr>, Box<Expr>),
     Vec(Vec<Expr>),
-    Cmd(&'static str, fn(Expr) -> Expr),
+    Cmd(Cmd),
     Call(Box<Expr>, Box<Expr>),
-    Err(String),
+    /// A user-defined function with its parameters and body.
+    Lambda(Vec<String>, Box<Expr>),
+    Series(Box<Series>),
+    Inf(Sign),
+    Eq(Box<Expr>, Box<Expr>),
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Sign {
+    Pos,
+    Neg,
+}
+
+impl Sign {
+    fn of(n: &Num) -> Option<Sign> {
+        match n.signum() {
+            0 => None,
+            1 => Some(Sign::Pos),
+            _ => Some(Sign::Neg),
+        }
+    }
+}
+
+impl std::ops::Mul for Sign {
+    type Output = Sign;
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self == rhs {
+            Sign::Pos
+        } else {
+            Sign::Neg
+        }
+    }
 }
 
 impl fmt::Debug for Expr {
@@ -32,13 +69,35 @@ impl fmt::Debug for Expr {
             Expr::Mul(m) => fmt_expr_list(f, &m.clone().into_args(), " * "),
             Expr::Pow(b, e) => write!(f, "({:?} ^ {:?})", b, e),
             Expr::Vec(v) => fmt_expr_list(f, v, ", "),
-            Expr::Cmd(s, _) => write!(f, "{}", s),
+            Expr::Cmd(c) => write!(f, "{:?}", c),
             Expr::Call(g, x) => write!(f, "{:?}({:?})", g, x),
-            Expr::Err(s) => write!(f, "{}", s),
+            Expr::Lambda(p, b) if p.len() == 1 => {
+                write!(f, "({} \\to {:?})", p[0], b)
+            }
+            Expr::Lambda(p, b) => {
+                write!(f, "(({}) \\to {:?})", p.join(", "), b)
+            }
+            Expr::Series(s) => write!(f, "{:?}", s),
+            Expr::Inf(Sign::Pos) => write!(f, "\\infty"),
+            Expr::Inf(Sign::Neg) => write!(f, "-\\infty"),
+            Expr::Eq(l, r) => write!(f, "({:?} = {:?})", l, r),
         }
     }
 }
 
+/// Multiply out the product of two sums.
+fn distribute(e1: Expr, e2: Expr) -> Expr {
+    let terms = |e| match e {
+        Expr::Add(a) => a.into_args(),
+        e => vec![e],
+    };
+    let (t1, t2) = (terms(e1), terms(e2));
+    Expr::sum(
+        t1.iter()
+            .flat_map(|a| t2.iter().map(move |b| a.clone() * b.clone())),
+    )
+}
+
 fn fmt_expr_list(
     f: &mut fmt::Formatter<'_>,
     v: &[Expr],
@@ -72,10 +131,6 @@ impl Expr {
     //     Expr::Num(Number::rational(num, den))
     // }
 
-    pub fn err(s: impl Into<String>) -> Expr {
-        Expr::Err(s.into())
-    }
-
     fn into_coeff_mul(self) -> (Num, Expr) {
         match self {
             Expr::Num(n) => (n, ONE),
@@ -87,22 +142,41 @@ impl Expr {
     pub fn pow(self, exp: Expr) -> Expr {
         // TODO: 0^0 = 1?
         match (self, exp) {
-            (Expr::Err(mut s1), Expr::Err(s2)) => {
-                s1.push('\n');
-                s1.push_str(&s2);
-                Expr::err(s1)
+            // Operands rejected by `try_pow` are left as they are.
+            (base @ Expr::Vec(_), exp) | (base, exp @ Expr::Vec(_)) => {
+                Expr::Pow(Box::new(base), Box::new(exp))
             }
-            (Expr::Vec(_), _) | (_, Expr::Vec(_)) => {
-                let s = String::from("unsupported operand");
-                Expr::err(s)
+            (base @ Expr::Inf(_), exp) | (base, exp @ Expr::Inf(_)) => {
+                let (b, e) = (base.clone(), exp.clone());
+                pow_inf(base, exp)
+                    .unwrap_or_else(|_| Expr::Pow(Box::new(b), Box::new(e)))
             }
             (_, ZERO) => ONE,
             (base, ONE) => base,
+            (Expr::Series(s), Expr::Num(exp)) => {
+                let base = Expr::Series(s.clone());
+                series::pow(*s, exp.clone()).unwrap_or_else(|_| {
+                    Expr::Pow(Box::new(base), Box::new(Expr::Num(exp)))
+                })
+            }
+            (base @ Expr::Series(_), exp)
+            | (base, exp @ Expr::Series(_))
+            | (base @ Expr::Eq(..), exp)
+            | (base, exp @ Expr::Eq(..)) => {
+                Expr::Pow(Box::new(base), Box::new(exp))
+            }
             (ZERO, _) => ZERO,
             (ONE, _) => ONE,
             (Expr::Num(base), Expr::Num(Num::Int(exp))) => {
                 Expr::Num(base.pow(exp))
             }
+            (Expr::Num(base), Expr::Num(Num::Rat(p, 2))) => match base.sqrt() {
+                Some(r) if p > 0 || r.signum() != 0 => Expr::Num(r.pow(p)),
+                _ => Expr::Pow(
+                    Box::new(Expr::Num(base)),
+                    Box::new(Expr::Num(Num::Rat(p, 2))),
+                ),
+            },
             (Expr::Mul(mul), exp @ Expr::Num(_)) => Expr::prod(
                 mul.into_args()
                     .into_iter()
@@ -113,17 +187,380 @@ impl Expr {
         }
     }
 
+    /// Call `callable`, keeping the call unevaluated if it fails.
     pub fn call(callable: Expr, argument: Expr) -> Expr {
-        match Expr::into_coeff_mul(callable) {
-            (c, Expr::Cmd(_, f)) => Expr::Num(c) * f(argument),
-            _ => Expr::err(""),
+        let env = Environment::new();
+        Expr::call_in(callable.clone(), argument.clone(), &env)
+            .unwrap_or_else(|_| Expr::unevaluated_call(callable, argument))
+    }
+
+    /// Call `callable` giving builtins access to `env`. A vector argument
+    /// holds the arguments unless `callable` is a lambda of one parameter.
+    pub fn call_in(
+        callable: Expr,
+        argument: Expr,
+        env: &Environment,
+    ) -> Result<Expr, EvalError> {
+        Expr::invoke(callable, env, |f| match argument {
+            Expr::Vec(v) if !matches!(f, Expr::Lambda(p, _) if p.len() == 1) => {
+                v
+            }
+            a => vec![a],
+        })
+    }
+
+    /// Call `callable` with the arguments `args` of a call `f(a, b, ...)`.
+    pub fn call_args(
+        callable: Expr,
+        args: Vec<Expr>,
+        env: &Environment,
+    ) -> Result<Expr, EvalError> {
+        Expr::invoke(callable, env, |_| args)
+    }
+
+    /// Call `callable` with the arguments returned by `args` for the
+    /// function without its coefficient.
+    fn invoke<F>(
+        callable: Expr,
+        env: &Environment,
+        args: F,
+    ) -> Result<Expr, EvalError>
+    where
+        F: FnOnce(&Expr) -> Vec<Expr>,
+    {
+        let (c, f) = Expr::into_coeff_mul(callable);
+        let args = args(&f);
+        let (c, r) = match (c, f) {
+            (c, Expr::Cmd(f)) => (c, f.call_args(args, env)?),
+            (c, Expr::Lambda(params, body)) => {
+                (c, Expr::apply(&params, *body, args, env)?)
+            }
+            (_, f) => {
+                let s = format!("{:?} is not a function", f);
+                return Err(EvalError::type_mismatch(s));
+            }
+        };
+        if c == num::ONE {
+            Ok(r)
+        } else {
+            Expr::Num(c).try_mul(r)
+        }
+    }
+
+    /// Substitute the arguments for the parameters of a lambda, calling the
+    /// functions in its body in `env`.
+    fn apply(
+        params: &[String],
+        body: Expr,
+        args: Vec<Expr>,
+        env: &Environment,
+    ) -> Result<Expr, EvalError> {
+        if args.len() != params.len() {
+            return Err(EvalError::arity("arguments error"));
         }
+        // Rename the parameters first so that an argument mentioning
+        // another parameter is not substituted again.
+        let dummies: Vec<String> =
+            (0..params.len()).map(|i| format!("\\_{}", i)).collect();
+        let body = params
+            .iter()
+            .zip(&dummies)
+            .fold(body, |b, (p, d)| b.subs(p, &Expr::Sym(d.clone())));
+        dummies
+            .iter()
+            .zip(args)
+            .try_fold(body, |b, (d, a)| b.try_subs_in(d, &a, env))
+    }
+
+    /// Return the name if `self` is a builtin.
+    pub fn cmd_name(&self) -> Option<&'static str> {
+        match self {
+            Expr::Cmd(c) => Some(c.name()),
+            _ => None,
+        }
+    }
+
+    pub fn is_cmd(&self, name: &str) -> bool {
+        self.cmd_name() == Some(name)
     }
 
     pub fn unevaluated_call(callable: Expr, argument: Expr) -> Expr {
         Expr::Call(Box::new(callable), Box::new(argument))
     }
 
+    pub fn has_sym(&self, sym: &str) -> bool {
+        match self {
+            Expr::Sym(s) => s == sym,
+            Expr::Add(a) => a.has_sym(sym),
+            Expr::Mul(m) => m
+                .terms
+                .iter()
+                .any(|(e, c)| e.has_sym(sym) || c.has_sym(sym)),
+            Expr::Pow(b, e) => b.has_sym(sym) || e.has_sym(sym),
+            Expr::Vec(v) => v.iter().any(|e| e.has_sym(sym)),
+            Expr::Call(f, x) => f.has_sym(sym) || x.has_sym(sym),
+            Expr::Lambda(p, b) => !p.iter().any(|p| p == sym) && b.has_sym(sym),
+            Expr::Series(s) => s.has_sym(sym),
+            Expr::Eq(l, r) => l.has_sym(sym) || r.has_sym(sym),
+            Expr::Num(_) | Expr::Cmd(..) | Expr::Inf(_) => false,
+        }
+    }
+
+    /// Replace `sym` by `value` and evaluate the result again.
+    pub fn subs(self, sym: &str, value: &Expr) -> Expr {
+        match self {
+            Expr::Sym(s) if s == sym => value.clone(),
+            Expr::Add(a) => {
+                Expr::sum(a.into_args().into_iter().map(|e| e.subs(sym, value)))
+            }
+            Expr::Mul(m) => Expr::prod(
+                m.into_args().into_iter().map(|e| e.subs(sym, value)),
+            ),
+            Expr::Pow(b, e) => {
+                Expr::pow(b.subs(sym, value), e.subs(sym, value))
+            }
+            Expr::Vec(v) => {
+                Expr::Vec(v.into_iter().map(|e| e.subs(sym, value)).collect())
+            }
+            Expr::Call(f, x) => Expr::call(*f, x.subs(sym, value)),
+            Expr::Lambda(p, b) if !p.iter().any(|p| p == sym) => {
+                let (p, b) = Expr::rename_params(p, *b, sym, value);
+                Expr::Lambda(p, Box::new(b.subs(sym, value)))
+            }
+            Expr::Eq(l, r) => Expr::Eq(
+                Box::new(l.subs(sym, value)),
+                Box::new(r.subs(sym, value)),
+            ),
+            e => e,
+        }
+    }
+
+    /// Replace `sym` by `value` and evaluate the changed subexpressions
+    /// again, failing if any of them does.
+    pub fn try_subs(self, sym: &str, value: &Expr) -> Result<Expr, EvalError> {
+        self.try_subs_in(sym, value, &Environment::new())
+    }
+
+    /// Substitute `value` for `sym` like `try_subs`, calling functions in
+    /// `env`.
+    pub fn try_subs_in(
+        self,
+        sym: &str,
+        value: &Expr,
+        env: &Environment,
+    ) -> Result<Expr, EvalError> {
+        if !self.has_sym(sym) {
+            return Ok(self);
+        }
+        let r = match self {
+            Expr::Sym(_) => value.clone(),
+            Expr::Add(a) => {
+                a.into_args().into_iter().try_fold(ZERO, |r, e| {
+                    r.try_add(e.try_subs_in(sym, value, env)?)
+                })?
+            }
+            Expr::Mul(m) => {
+                m.into_args().into_iter().try_fold(ONE, |r, e| {
+                    r.try_mul(e.try_subs_in(sym, value, env)?)
+                })?
+            }
+            Expr::Pow(b, e) => b
+                .try_subs_in(sym, value, env)?
+                .try_pow(e.try_subs_in(sym, value, env)?)?,
+            Expr::Vec(v) => Expr::Vec(
+                v.into_iter()
+                    .map(|e| e.try_subs_in(sym, value, env))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Expr::Call(f, x) => {
+                Expr::call_in(*f, x.try_subs_in(sym, value, env)?, env)?
+            }
+            Expr::Lambda(p, b) => {
+                let (p, b) = Expr::rename_params(p, *b, sym, value);
+                Expr::Lambda(p, Box::new(b.try_subs_in(sym, value, env)?))
+            }
+            Expr::Eq(l, r) => Expr::Eq(
+                Box::new(l.try_subs_in(sym, value, env)?),
+                Box::new(r.try_subs_in(sym, value, env)?),
+            ),
+            e => e,
+        };
+        Ok(r)
+    }
+
+    /// Rename the parameters of a lambda which occur in `value` before it
+    /// is substituted for `sym` in `body`, so that they do not capture its
+    /// symbols. A parameter `y` becomes the first of `y_1`, `y_2`, ... which
+    /// is not used.
+    fn rename_params(
+        params: Vec<String>,
+        body: Expr,
+        sym: &str,
+        value: &Expr,
+    ) -> (Vec<String>, Expr) {
+        if !body.has_sym(sym) {
+            return (params, body);
+        }
+        let mut body = body;
+        let mut renamed = params.clone();
+        for (i, p) in params.iter().enumerate() {
+            if !value.has_sym(p) {
+                continue;
+            }
+            let fresh = (1..)
+                .map(|k| format!("{}_{}", p, k))
+                .find(|q| {
+                    !value.has_sym(q)
+                        && !body.has_sym(q)
+                        && !renamed.contains(q)
+                })
+                .unwrap();
+            body = body.subs(p, &Expr::Sym(fresh.clone()));
+            renamed[i] = fresh;
+        }
+        (renamed, body)
+    }
+
+    /// Return `self + rhs`, or an error if the operands are incompatible.
+    pub fn try_add(self, rhs: Expr) -> Result<Expr, EvalError> {
+        match (self, rhs) {
+            (Expr::Vec(v1), Expr::Vec(v2)) if v1.len() == v2.len() => {
+                let v = v1
+                    .into_iter()
+                    .zip(v2)
+                    .map(|(e1, e2)| e1.try_add(e2))
+                    .collect::<Result<_, _>>()?;
+                Ok(Expr::Vec(v))
+            }
+            (Expr::Vec(v1), Expr::Vec(v2)) => {
+                Err(EvalError::type_mismatch(format!(
+                    "unsupported operand: +: \\R^{} x \\R^{} -> ?",
+                    v1.len(),
+                    v2.len()
+                )))
+            }
+            (Expr::Vec(_), _)
+            | (_, Expr::Vec(_))
+            | (Expr::Eq(..), _)
+            | (_, Expr::Eq(..)) => {
+                Err(EvalError::type_mismatch("unsupported operand"))
+            }
+            (Expr::Inf(s1), Expr::Inf(s2)) if s1 != s2 => {
+                Err(EvalError::undefined("indeterminate form"))
+            }
+            (e @ Expr::Inf(_), _) | (_, e @ Expr::Inf(_)) => Ok(e),
+            (Expr::Series(s), e) | (e, Expr::Series(s)) => series::add(*s, e),
+            (Expr::Num(n1), Expr::Num(n2)) => n1
+                .checked_add(&n2)
+                .map(Expr::Num)
+                .ok_or_else(|| EvalError::overflow("integer overflow")),
+            (e1, e2) => Ok(e1 + e2),
+        }
+    }
+
+    pub fn try_sub(self, rhs: Expr) -> Result<Expr, EvalError> {
+        self.try_add(rhs.try_mul(NEG_ONE)?)
+    }
+
+    /// Return `self * rhs`, or an error if the operands are incompatible.
+    pub fn try_mul(self, rhs: Expr) -> Result<Expr, EvalError> {
+        match (self, rhs) {
+            (Expr::Vec(_), Expr::Vec(_))
+            | (Expr::Eq(..), _)
+            | (_, Expr::Eq(..)) => {
+                Err(EvalError::type_mismatch("unsupported operand"))
+            }
+            (Expr::Vec(v), c) | (c, Expr::Vec(v)) => {
+                let v = v
+                    .into_iter()
+                    .map(|e| e.try_mul(c.clone()))
+                    .collect::<Result<_, _>>()?;
+                Ok(Expr::Vec(v))
+            }
+            (Expr::Inf(_), ZERO) | (ZERO, Expr::Inf(_)) => {
+                Err(EvalError::undefined("indeterminate form"))
+            }
+            (Expr::Series(s), e) | (e, Expr::Series(s))
+                if !matches!(e, Expr::Inf(_)) =>
+            {
+                series::mul(*s, e)
+            }
+            (Expr::Num(n1), Expr::Num(n2)) => n1
+                .checked_mul(&n2)
+                .map(Expr::Num)
+                .ok_or_else(|| EvalError::overflow("integer overflow")),
+            (e1, e2) => Ok(e1 * e2),
+        }
+    }
+
+    pub fn try_div(self, rhs: Expr) -> Result<Expr, EvalError> {
+        self.try_mul(rhs.try_pow(NEG_ONE)?)
+    }
+
+    /// Return `self ^ exp`, or an error if the operands are incompatible.
+    pub fn try_pow(self, exp: Expr) -> Result<Expr, EvalError> {
+        match (self, exp) {
+            (Expr::Vec(_), _)
+            | (_, Expr::Vec(_))
+            | (Expr::Cmd(_), _)
+            | (_, Expr::Cmd(_))
+            | (Expr::Lambda(..), _)
+            | (_, Expr::Lambda(..))
+            | (Expr::Eq(..), _)
+            | (_, Expr::Eq(..))
+            | (_, Expr::Series(_)) => {
+                Err(EvalError::type_mismatch("unsupported operand"))
+            }
+            (Expr::Series(_), exp) if !matches!(exp, Expr::Num(_)) => {
+                Err(EvalError::type_mismatch("unsupported operand"))
+            }
+            (ZERO, Expr::Num(n)) if n.signum() < 0 => {
+                Err(EvalError::domain("division by zero"))
+            }
+            (Expr::Series(s), Expr::Num(exp)) => series::pow(*s, exp),
+            (base @ Expr::Inf(_), exp) | (base, exp @ Expr::Inf(_)) => {
+                pow_inf(base, exp)
+            }
+            (Expr::Num(base), Expr::Num(Num::Int(exp))) => base
+                .checked_pow(exp)
+                .map(Expr::Num)
+                .ok_or_else(|| EvalError::overflow("integer overflow")),
+            (base, exp) => Ok(Expr::pow(base, exp)),
+        }
+    }
+
+    /// Distribute products and positive integer powers over sums.
+    pub fn expand(self) -> Expr {
+        match self {
+            Expr::Add(a) => {
+                Expr::sum(a.into_args().into_iter().map(Expr::expand))
+            }
+            Expr::Mul(m) => m
+                .into_args()
+                .into_iter()
+                .fold(ONE, |r, e| distribute(r, e.expand())),
+            Expr::Pow(b, e) => match *e {
+                Expr::Num(Num::Int(n)) if n > 1 => {
+                    let b = b.expand();
+                    (0..n).fold(ONE, |r, _| distribute(r, b.clone()))
+                }
+                e => Expr::pow(b.expand(), e),
+            },
+            Expr::Vec(v) => {
+                Expr::Vec(v.into_iter().map(Expr::expand).collect())
+            }
+            // Only elementary functions are evaluated again; other calls
+            // are unevaluated results of commands.
+            Expr::Call(f, x) => match *f {
+                Expr::Cmd(c) if func::is_elementary(c.name()) => {
+                    Expr::call(*f, x.expand())
+                }
+                f => Expr::unevaluated_call(f, x.expand()),
+            },
+            e => e,
+        }
+    }
+
     pub fn sum<I>(iter: I) -> Expr
     where
         I: IntoIterator<Item = Expr>,
@@ -147,36 +584,93 @@ impl Expr {
     }
 }
 
+/// Return `base ^ exp` where either is infinite, or an error for the
+/// indeterminate forms `\infty^0` and `1^\infty` and for powers without
+/// a limit.
+fn pow_inf(base: Expr, exp: Expr) -> Result<Expr, EvalError> {
+    let indeterminate = || EvalError::undefined("indeterminate form");
+    let no_limit = || EvalError::undefined("limit does not exist");
+    match (base, exp) {
+        (Expr::Inf(_), Expr::Num(n)) if n.signum() == 0 => Err(indeterminate()),
+        (Expr::Inf(_), Expr::Num(n)) if n.signum() < 0 => Ok(ZERO),
+        (Expr::Inf(s), Expr::Num(n)) => {
+            // The sign of `(-\infty)^(p/q)` is that of `(-1)^(p/q)`.
+            let (p, q) = n.parts();
+            match s {
+                Sign::Pos => Ok(Expr::Inf(Sign::Pos)),
+                Sign::Neg if q % 2 == 0 => {
+                    Err(EvalError::domain("even root of a negative number"))
+                }
+                Sign::Neg if p % 2 != 0 => Ok(Expr::Inf(Sign::Neg)),
+                Sign::Neg => Ok(Expr::Inf(Sign::Pos)),
+            }
+        }
+        (Expr::Num(n), Expr::Inf(s)) => {
+            let (p, q) = n.parts();
+            let grows = match p.unsigned_abs().cmp(&q.unsigned_abs()) {
+                Ordering::Equal if p > 0 => return Err(indeterminate()),
+                Ordering::Equal => return Err(no_limit()),
+                Ordering::Greater => s == Sign::Pos,
+                Ordering::Less if p == 0 && s == Sign::Neg => {
+                    return Err(EvalError::domain("division by zero"))
+                }
+                Ordering::Less => s == Sign::Neg,
+            };
+            match (grows, p > 0) {
+                (false, _) => Ok(ZERO),
+                (true, true) => Ok(Expr::Inf(Sign::Pos)),
+                (true, false) => Err(no_limit()),
+            }
+        }
+        (Expr::Inf(_), Expr::Inf(Sign::Neg)) => Ok(ZERO),
+        (Expr::Inf(Sign::Pos), Expr::Inf(Sign::Pos)) => {
+            Ok(Expr::Inf(Sign::Pos))
+        }
+        (Expr::Inf(Sign::Neg), Expr::Inf(Sign::Pos)) => Err(no_limit()),
+        (base, exp) => Ok(Expr::Pow(Box::new(base), Box::new(exp))),
+    }
+}
+
+/// Collect two terms in a sum without evaluating them together.
+fn add_terms(e1: Expr, e2: Expr) -> Expr {
+    let mut a = Add::new();
+    a.add_assign(e1);
+    a.add_assign(e2);
+    a.into_expr()
+}
+
+/// Collect two factors in a product without evaluating them together.
+fn mul_factors(e1: Expr, e2: Expr) -> Expr {
+    let mut m = Mul::new();
+    m.mul_assign(e1);
+    m.mul_assign(e2);
+    m.into_expr()
+}
+
 impl std::ops::Add for Expr {
     type Output = Expr;
     fn add(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            (Expr::Err(mut s1), Expr::Err(s2)) => {
-                s1.push('\n');
-                s1.push_str(&s2);
-                Expr::err(s1)
-            }
-            (e @ Expr::Err(_), _) | (_, e @ Expr::Err(_)) => e,
             (Expr::Vec(mut v1), Expr::Vec(v2)) if v1.len() == v2.len() => {
-                for (e1, e2) in v1.iter_mut().zip(v2.into_iter()) {
+                for (e1, e2) in v1.iter_mut().zip(v2) {
                     *e1 += e2;
                 }
                 Expr::Vec(v1)
             }
-            (Expr::Vec(v1), Expr::Vec(v2)) => Expr::err(format!(
-                "unsupported operand: +: \\R^{} x \\R^{} -> ?",
-                v1.len(),
-                v2.len()
-            )),
-            (Expr::Vec(_), _) | (_, Expr::Vec(_)) => {
-                Expr::err("unsupported operand")
+            // Operands rejected by `try_add` are collected in a sum.
+            (e1 @ Expr::Vec(_), e2)
+            | (e1, e2 @ Expr::Vec(_))
+            | (e1 @ Expr::Eq(..), e2)
+            | (e1, e2 @ Expr::Eq(..)) => add_terms(e1, e2),
+            (Expr::Inf(s1), Expr::Inf(s2)) if s1 != s2 => {
+                add_terms(Expr::Inf(s1), Expr::Inf(s2))
             }
-            (e1, e2) => {
-                let mut a = Add::new();
-                a.add_assign(e1);
-                a.add_assign(e2);
-                a.into_expr()
+            (e @ Expr::Inf(_), _) | (_, e @ Expr::Inf(_)) => e,
+            (Expr::Series(s), e) | (e, Expr::Series(s)) => {
+                let (s1, e1) = (Expr::Series(s.clone()), e.clone());
+                series::add(*s, e).unwrap_or_else(|_| add_terms(s1, e1))
             }
+            (e1, e2) => add_terms(e1, e2),
         }
     }
 }
@@ -194,28 +688,39 @@ impl std::ops::Sub for Expr {
     }
 }
 
+impl std::ops::SubAssign for Expr {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = std::mem::take(self) - rhs;
+    }
+}
+
 impl std::ops::Mul for Expr {
     type Output = Expr;
     fn mul(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            (Expr::Err(mut s1), Expr::Err(s2)) => {
-                s1.push('\n');
-                s1.push_str(&s2);
-                Expr::err(s1)
-            }
-            (Expr::Vec(_), Expr::Vec(_)) => Expr::err("unsupported operand"),
+            // Operands rejected by `try_mul` are collected in a product.
+            (e1 @ Expr::Vec(_), e2 @ Expr::Vec(_))
+            | (e1 @ Expr::Eq(..), e2)
+            | (e1, e2 @ Expr::Eq(..)) => mul_factors(e1, e2),
             (Expr::Vec(mut v), c) | (c, Expr::Vec(mut v)) => {
                 for e in &mut v {
                     *e *= c.clone();
                 }
                 Expr::Vec(v)
             }
-            (e1, e2) => {
-                let mut m = Mul::new();
-                m.mul_assign(e1);
-                m.mul_assign(e2);
-                m.into_expr()
+            (Expr::Inf(s1), Expr::Inf(s2)) => Expr::Inf(s1 * s2),
+            (Expr::Inf(s), Expr::Num(n)) | (Expr::Num(n), Expr::Inf(s)) => {
+                match Sign::of(&n) {
+                    Some(t) => Expr::Inf(s * t),
+                    // `0 * \infty` is rejected by `try_mul`.
+                    None => ZERO,
+                }
+            }
+            (Expr::Series(s), e) | (e, Expr::Series(s)) => {
+                let (s1, e1) = (Expr::Series(s.clone()), e.clone());
+                series::mul(*s, e).unwrap_or_else(|_| mul_factors(s1, e1))
             }
+            (e1, e2) => mul_factors(e1, e2),
         }
     }
 }
@@ -229,7 +734,7 @@ impl std::ops::MulAssign for Expr {
 impl std::ops::Div for Expr {
     type Output = Expr;
     fn div(self, rhs: Self) -> Self::Output {
-        self * Expr::pow(rhs, NEG_ONE)
+        std::ops::Mul::mul(self, Expr::pow(rhs, NEG_ONE))
     }
 }
 
@@ -298,6 +803,10 @@ impl Add {
     fn is_zero(&self) -> bool {
         self.coeff == num::ZERO && self.terms.is_empty()
     }
+
+    fn has_sym(&self, sym: &str) -> bool {
+        self.terms.keys().any(|e| e.has_sym(sym))
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -314,6 +823,11 @@ impl Mul {
         }
     }
 
+    /// Return the numeric factor.
+    pub fn coeff(&self) -> &Num {
+        &self.coeff
+    }
+
     pub fn into_args(self) -> Vec<Expr> {
         let mut args = Vec::new();
         if self.coeff != num::ONE {
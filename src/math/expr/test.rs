@@ -26,8 +26,7 @@ fn test_add_expr() {
     ];
 
     for (input, expected) in tests {
-        let expr = parse_expr(input);
-        assert_eq!(format!("{:?}", expr), expected);
+        assert_eq!(eval_str(input), expected);
     }
 }
 
@@ -43,8 +42,7 @@ fn test_mul_expr() {
     ];
 
     for (input, expected) in tests {
-        let expr = parse_expr(input);
-        assert_eq!(format!("{:?}", expr), expected);
+        assert_eq!(eval_str(input), expected);
     }
 }
 
@@ -60,8 +58,7 @@ fn test_pow_expr() {
     ];
 
     for (input, expected) in tests {
-        let expr = parse_expr(input);
-        assert_eq!(format!("{:?}", expr), expected);
+        assert_eq!(eval_str(input), expected);
     }
 }
 
@@ -86,8 +83,7 @@ fn test_expr() {
     ];
 
     for (input, expected) in tests {
-        let expr = parse_expr(input);
-        assert_eq!(format!("{:?}", expr), expected);
+        assert_eq!(eval_str(input), expected);
     }
 }
 
@@ -110,7 +106,33 @@ fn test_vec_expr() {
     ];
 
     for (input, expected) in tests {
-        let expr = parse_expr(input);
-        assert_eq!(format!("{:?}", expr), expected);
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_inf_expr() {
+    let tests = [
+        (r"\infty + 1", r"\infty"),
+        (r"-\infty", r"-\infty"),
+        (r"\infty + \infty", r"\infty"),
+        (r"\infty - \infty", "indeterminate form"),
+        (r"-2 \infty", r"-\infty"),
+        (r"0 \infty", "indeterminate form"),
+        (r"(-\infty)^3", r"-\infty"),
+        (r"(-\infty)^2", r"\infty"),
+        (r"1/\infty", "0"),
+        (r"\infty^0", "indeterminate form"),
+        (r"1^\infty", "indeterminate form"),
+        (r"(-\infty)^(1/2)", "even root of a negative number"),
+        (r"(-\infty)^(1/3)", r"-\infty"),
+        (r"(1/2)^\infty", "0"),
+        (r"(1/2)^-\infty", r"\infty"),
+        (r"(-2)^\infty", "limit does not exist"),
+        (r"\infty^-\infty", "0"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
     }
 }
@@ -0,0 +1,283 @@
+use super::expr::{Expr, Sign, NEG_ONE};
+use super::num;
+
+pub mod asciimath;
+pub mod infix;
+pub mod latex;
+pub mod mathml;
+pub mod pretty;
+
+/// Notation in which results are written.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Format {
+    /// Fully parenthesized as in `((x ^ 2) + 1)`.
+    #[default]
+    Plain,
+    Infix,
+    Pretty,
+    Unicode,
+    Latex,
+    MathML,
+    AsciiMath,
+}
+
+impl Format {
+    pub const ALL: [Format; 7] = [
+        Format::Plain,
+        Format::Infix,
+        Format::Pretty,
+        Format::Unicode,
+        Format::Latex,
+        Format::MathML,
+        Format::AsciiMath,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Plain => "plain",
+            Format::Infix => "infix",
+            Format::Pretty => "pretty",
+            Format::Unicode => "unicode",
+            Format::Latex => "latex",
+            Format::MathML => "mathml",
+            Format::AsciiMath => "asciimath",
+        }
+    }
+
+    /// Return the format called `name`, ignoring case.
+    pub fn from_name(name: &str) -> Option<Format> {
+        Format::ALL
+            .iter()
+            .copied()
+            .find(|f| f.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Return `e` written in the format `f`.
+pub fn render(e: &Expr, f: Format) -> String {
+    match f {
+        Format::Plain => format!("{:?}", e),
+        Format::Infix => e.to_string(),
+        Format::Pretty => pretty::pretty(e, false),
+        Format::Unicode => pretty::pretty(e, true),
+        Format::Latex => e.to_latex(),
+        Format::MathML => e.to_mathml(),
+        Format::AsciiMath => e.to_asciimath(),
+    }
+}
+
+/// Functions written with their own name in mathematical notation.
+pub const OPERATORS: [&str; 12] = [
+    "\\arccos", "\\arcsin", "\\arctan", "\\cos", "\\cosh", "\\exp", "\\ln",
+    "\\log", "\\sin", "\\sinh", "\\tan", "\\tanh",
+];
+
+/// LaTeX commands of the Greek letters and the letters they stand for.
+pub const GREEK: [(&str, char); 35] = [
+    ("\\alpha", 'α'),
+    ("\\beta", 'β'),
+    ("\\gamma", 'γ'),
+    ("\\delta", 'δ'),
+    ("\\epsilon", 'ϵ'),
+    ("\\varepsilon", 'ε'),
+    ("\\zeta", 'ζ'),
+    ("\\eta", 'η'),
+    ("\\theta", 'θ'),
+    ("\\vartheta", 'ϑ'),
+    ("\\iota", 'ι'),
+    ("\\kappa", 'κ'),
+    ("\\lambda", 'λ'),
+    ("\\mu", 'μ'),
+    ("\\nu", 'ν'),
+    ("\\xi", 'ξ'),
+    ("\\pi", 'π'),
+    ("\\rho", 'ρ'),
+    ("\\sigma", 'σ'),
+    ("\\tau", 'τ'),
+    ("\\upsilon", 'υ'),
+    ("\\phi", 'ϕ'),
+    ("\\varphi", 'φ'),
+    ("\\chi", 'χ'),
+    ("\\psi", 'ψ'),
+    ("\\omega", 'ω'),
+    ("\\Gamma", 'Γ'),
+    ("\\Delta", 'Δ'),
+    ("\\Theta", 'Θ'),
+    ("\\Lambda", 'Λ'),
+    ("\\Xi", 'Ξ'),
+    ("\\Pi", 'Π'),
+    ("\\Sigma", 'Σ'),
+    ("\\Phi", 'Φ'),
+    ("\\Omega", 'Ω'),
+];
+
+/// Binding strength of the outermost operation of an expression in infix
+/// notation, weakest first.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum Prec {
+    Lambda,
+    Eq,
+    Sum,
+    Product,
+    Power,
+    Atom,
+}
+
+pub fn prec(e: &Expr) -> Prec {
+    match e {
+        Expr::Eq(..) => Prec::Eq,
+        Expr::Lambda(..) => Prec::Lambda,
+        Expr::Add(_) | Expr::Series(_) | Expr::Inf(Sign::Neg) => Prec::Sum,
+        Expr::Num(_) | Expr::Mul(_) | Expr::Pow(..) => {
+            let f = fraction(e.clone());
+            if f.sign == Sign::Neg {
+                Prec::Sum
+            } else if !f.den.is_empty() || f.num.len() > 1 {
+                Prec::Product
+            } else if let Some(Expr::Pow(..)) = f.num.first() {
+                Prec::Power
+            } else {
+                Prec::Atom
+            }
+        }
+        _ => Prec::Atom,
+    }
+}
+
+/// Return `e` written by `write`, enclosed by `paren` if it binds less
+/// tightly than `p`.
+pub fn operand(
+    e: &Expr,
+    p: Prec,
+    write: fn(&Expr) -> String,
+    paren: fn(&str) -> String,
+) -> String {
+    if prec(e) < p {
+        paren(&write(e))
+    } else {
+        write(e)
+    }
+}
+
+/// Return a sum subtracting the terms with a negative sign. The terms are
+/// written by `term` after the strings in `signs`, which go before a
+/// negative first term and before the other positive and negative terms.
+pub fn sum<F>(terms: Vec<Expr>, signs: [&str; 3], term: F) -> String
+where
+    F: Fn(&Expr) -> String,
+{
+    let mut s = String::new();
+    for (i, t) in terms.into_iter().enumerate() {
+        let (sign, t) = split_sign(t);
+        s.push_str(match (i, sign) {
+            (0, Sign::Pos) => "",
+            (0, Sign::Neg) => signs[0],
+            (_, Sign::Pos) => signs[1],
+            (_, Sign::Neg) => signs[2],
+        });
+        s.push_str(&term(&t));
+    }
+    s
+}
+
+/// Split a symbol into its name and the subscript after `_`, without the
+/// braces around a subscript of several characters.
+pub fn subscript(s: &str) -> (&str, Option<&str>) {
+    match s.split_once('_') {
+        Some((name, sub)) if !name.is_empty() => {
+            let sub = match sub.strip_prefix('{') {
+                Some(sub) => sub.strip_suffix('}').unwrap_or(sub),
+                None => sub,
+            };
+            (name, Some(sub))
+        }
+        _ => (s, None),
+    }
+}
+
+/// Split a term of a sum into its sign and its absolute value.
+pub fn split_sign(e: Expr) -> (Sign, Expr) {
+    match e {
+        Expr::Num(n) if n.signum() < 0 => {
+            (Sign::Neg, Expr::Num(n * num::NEG_ONE))
+        }
+        Expr::Inf(Sign::Neg) => (Sign::Neg, Expr::Inf(Sign::Pos)),
+        Expr::Mul(m) if m.coeff().signum() < 0 => {
+            (Sign::Neg, NEG_ONE * Expr::Mul(m))
+        }
+        e => (Sign::Pos, e),
+    }
+}
+
+/// A product split into its sign and the factors above and below the
+/// fraction bar.
+#[derive(Debug, PartialEq)]
+pub struct Fraction {
+    pub sign: Sign,
+    /// The factors of the numerator, empty if it is 1.
+    pub num: Vec<Expr>,
+    /// The factors of the denominator with their exponents made positive.
+    pub den: Vec<Expr>,
+}
+
+/// Split `e` into a fraction. The numerator and the denominator of the
+/// coefficient come first in their lists unless they are 1.
+pub fn fraction(e: Expr) -> Fraction {
+    let (sign, e) = split_sign(e);
+    let factors = match e {
+        Expr::Mul(m) => m.into_args(),
+        e => vec![e],
+    };
+    let mut f = Fraction {
+        sign,
+        num: Vec::new(),
+        den: Vec::new(),
+    };
+    for e in factors {
+        match e {
+            Expr::Num(n) => {
+                let (p, q) = n.parts();
+                if p != 1 {
+                    f.num.push(Expr::int(p));
+                }
+                if q != 1 {
+                    f.den.push(Expr::int(q));
+                }
+            }
+            Expr::Pow(base, exp) => match split_sign(*exp) {
+                (Sign::Neg, exp) => f.den.push(Expr::pow(*base, exp)),
+                (Sign::Pos, exp) => f.num.push(Expr::Pow(base, Box::new(exp))),
+            },
+            e => f.num.push(e),
+        }
+    }
+    f
+}
+
+/// Return the arguments of a call.
+pub fn args(argument: &Expr) -> &[Expr] {
+    match argument {
+        Expr::Vec(v) => v,
+        e => std::slice::from_ref(e),
+    }
+}
+
+/// Return whether `f` is a builtin in `OPERATORS`.
+pub fn is_operator(f: &Expr) -> bool {
+    f.cmd_name().is_some_and(|s| OPERATORS.contains(&s))
+}
+
+/// Return the rows of a matrix, or `None` if `v` is not a list of lists
+/// of equal length.
+pub fn matrix(v: &[Expr]) -> Option<Vec<&[Expr]>> {
+    let width = match v.first() {
+        Some(Expr::Vec(r)) if !r.is_empty() => r.len(),
+        _ => return None,
+    };
+    v.iter()
+        .map(|e| match e {
+            Expr::Vec(r) if r.len() == width => Some(r.as_slice()),
+            _ => None,
+        })
+        .collect()
+}
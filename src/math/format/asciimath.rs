@@ -0,0 +1,164 @@
+use super::{args, fraction, matrix, subscript, Prec, OPERATORS};
+use crate::math::expr::{Expr, Sign};
+use crate::math::num::Num;
+
+#[cfg(test)]
+mod test;
+
+impl Expr {
+    /// Return AsciiMath markup of `self`.
+    pub fn to_asciimath(&self) -> String {
+        asciimath(self)
+    }
+}
+
+fn asciimath(e: &Expr) -> String {
+    match e {
+        Expr::Num(_) | Expr::Mul(_) | Expr::Pow(..) => product(e),
+        Expr::Sym(s) => symbol(s),
+        Expr::Add(a) => sum(a.clone().into_args()),
+        Expr::Vec(v) => match matrix(v) {
+            Some(rows) => {
+                let rows: Vec<_> =
+                    rows.iter().map(|r| paren(&list(r))).collect();
+                paren(&rows.join(", "))
+            }
+            None => paren(&list(v)),
+        },
+        Expr::Cmd(c) => function(c.name()),
+        Expr::Call(f, x) if f.is_cmd("\\factorial") => {
+            format!("{}!", operand(x, Prec::Atom))
+        }
+        Expr::Call(f, x) => {
+            format!("{}({})", operand(f, Prec::Atom), list(args(x)))
+        }
+        Expr::Lambda(p, b) => {
+            let p: Vec<_> = p.iter().map(|s| symbol(s)).collect();
+            let p = match p.len() {
+                1 => p[0].clone(),
+                _ => paren(&p.join(", ")),
+            };
+            format!("{} |-> {}", p, operand(b, Prec::Lambda))
+        }
+        Expr::Series(s) => {
+            let o = format!("O({})", asciimath(&s.order_term()));
+            match s.terms() {
+                t if t.is_empty() => o,
+                t => format!("{} + {}", sum(t), o),
+            }
+        }
+        Expr::Inf(Sign::Pos) => "oo".to_string(),
+        Expr::Inf(Sign::Neg) => "-oo".to_string(),
+        Expr::Eq(l, r) => {
+            format!("{} = {}", operand(l, Prec::Sum), operand(r, Prec::Sum))
+        }
+    }
+}
+
+fn paren(s: &str) -> String {
+    format!("({})", s)
+}
+
+fn operand(e: &Expr, p: Prec) -> String {
+    super::operand(e, p, asciimath, paren)
+}
+
+fn list(v: &[Expr]) -> String {
+    let v: Vec<_> = v.iter().map(|e| operand(e, Prec::Lambda)).collect();
+    v.join(", ")
+}
+
+/// Return a symbol with names of more than one letter quoted so that they
+/// are not read as products, but names such as `\pi` are AsciiMath symbols
+/// without the backslash.
+fn symbol(s: &str) -> String {
+    let (name, sub) = subscript(s);
+    let name = match name.strip_prefix('\\') {
+        Some(name) => name.to_string(),
+        None if name.chars().count() == 1 => name.to_string(),
+        None => format!("\"{}\"", name),
+    };
+    match sub.map(|s| s.trim_start_matches('\\')) {
+        Some(sub) if sub.chars().count() == 1 => format!("{}_{}", name, sub),
+        Some(sub) => format!("{}_({})", name, sub),
+        None => name,
+    }
+}
+
+/// Return the name of a builtin, quoted unless AsciiMath knows it.
+fn function(name: &str) -> String {
+    let bare = name.trim_start_matches('\\');
+    if OPERATORS.contains(&name) {
+        bare.to_string()
+    } else {
+        format!("\"{}\"", bare)
+    }
+}
+
+fn sum(terms: Vec<Expr>) -> String {
+    super::sum(terms, ["-", " + ", " - "], |t| operand(t, Prec::Product))
+}
+
+/// Return a product as a quotient if some factors have negative exponents.
+fn product(e: &Expr) -> String {
+    let f = fraction(e.clone());
+    let s = if f.den.is_empty() {
+        factors(&f.num)
+    } else {
+        format!("{}/{}", grouped(&f.num), grouped(&f.den))
+    };
+    match f.sign {
+        Sign::Pos => s,
+        Sign::Neg => format!("-{}", s),
+    }
+}
+
+/// Return the factors of a numerator or denominator, in parentheses unless
+/// there is a single one that binds tightly.
+fn grouped(v: &[Expr]) -> String {
+    match v {
+        [] => "1".to_string(),
+        [e @ Expr::Add(_)] => paren(&asciimath(e)),
+        [_] => factors(v),
+        v => paren(&factors(v)),
+    }
+}
+
+/// Return the factors side by side, with `*` before a number.
+fn factors(v: &[Expr]) -> String {
+    let mut s = String::new();
+    for e in v {
+        let t = match e {
+            Expr::Pow(base, exp) => power(base, exp),
+            Expr::Num(n) => format!("{:?}", n),
+            e => operand(e, Prec::Product),
+        };
+        if !s.is_empty() {
+            let digit = t.starts_with(|c: char| c.is_ascii_digit());
+            s.push_str(if digit { "*" } else { " " });
+        }
+        s.push_str(&t);
+    }
+    if s.is_empty() {
+        s.push('1');
+    }
+    s
+}
+
+fn power(base: &Expr, exp: &Expr) -> String {
+    match exp {
+        Expr::Num(Num::Rat(1, 2)) => format!("sqrt({})", asciimath(base)),
+        Expr::Num(Num::Rat(1, n)) => {
+            format!("root({})({})", n, asciimath(base))
+        }
+        _ => {
+            let exp = asciimath(exp);
+            let exp = if exp.chars().all(|c| c.is_alphanumeric()) {
+                exp
+            } else {
+                paren(&exp)
+            };
+            format!("{}^{}", operand(base, Prec::Atom), exp)
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use super::super::super::utl_test::*;
+
+#[test]
+fn test_to_asciimath() {
+    let tests = [
+        ("x^2 - 2x + 1", "-2 x + x^2 + 1"),
+        ("-x/2 + 3/4", "-x/2 + 3/4"),
+        ("(x + 1)/(x y^2)", "(x + 1)/(x y^2)"),
+        ("2*3^x", "2*3^x"),
+        ("x^(n + 1)", "x^(n + 1)"),
+        ("x^(1/2) + (x + 1)^(1/3)", "sqrt(x) + root(3)(x + 1)"),
+        ("\\sin(x)^2 \\cos(x)", "cos(x) sin(x)^2"),
+        ("\\factorial(n) x_1 ab \\pi", "pi a b x_1 n!"),
+        ("\\diff(f, x)", "\"diff\"(f, x)"),
+        ("((1, x), (0, 1))", "((1, x), (0, 1))"),
+        ("(x, y) \\to x y", "(x, y) |-> x y"),
+        ("\\series(\\exp(x), x, 0, 2)", "1 + x + O(x^2)"),
+        ("-\\infty", "-oo"),
+        ("x_{max}", "x_(max)"),
+        ("x_{10}", "x_(10)"),
+        ("x_{\\alpha}", "x_(alpha)"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(parse_expr(input).to_asciimath(), expected, "{}", input);
+    }
+}
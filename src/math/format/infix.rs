@@ -0,0 +1,137 @@
+use super::{args, fraction, prec, split_sign, Prec};
+use crate::math::expr::{Expr, Sign};
+use std::fmt;
+
+#[cfg(test)]
+mod test;
+
+/// Infix notation with as few parentheses as the parser needs to read it
+/// back.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Num(_) | Expr::Mul(_) | Expr::Pow(..) => fmt_product(f, self),
+            Expr::Sym(s) => write!(f, "{}", s),
+            Expr::Add(a) => fmt_sum(f, a.clone().into_args()),
+            Expr::Vec(v) => {
+                write!(f, "(")?;
+                fmt_list(f, v)?;
+                write!(f, ")")
+            }
+            Expr::Cmd(c) => write!(f, "{}", c.name()),
+            Expr::Call(g, x) => {
+                fmt_operand(f, g, Prec::Atom)?;
+                write!(f, "(")?;
+                fmt_list(f, args(x))?;
+                write!(f, ")")
+            }
+            Expr::Lambda(p, b) if p.len() == 1 => {
+                write!(f, "{} \\to ", p[0])?;
+                fmt_operand(f, b, Prec::Lambda)
+            }
+            Expr::Lambda(p, b) => {
+                write!(f, "({}) \\to ", p.join(", "))?;
+                fmt_operand(f, b, Prec::Lambda)
+            }
+            // The expansion of the sum of the terms gives the series again.
+            Expr::Series(s) => write!(
+                f,
+                "\\series({}, {}, {}, {})",
+                Expr::sum(s.terms()),
+                s.var(),
+                s.point(),
+                s.order()
+            ),
+            Expr::Inf(Sign::Pos) => write!(f, "\\infty"),
+            Expr::Inf(Sign::Neg) => write!(f, "-\\infty"),
+            Expr::Eq(l, r) => {
+                fmt_operand(f, l, Prec::Sum)?;
+                write!(f, " = ")?;
+                fmt_operand(f, r, Prec::Sum)
+            }
+        }
+    }
+}
+
+/// Write `e`, in parentheses if it binds less tightly than `p`.
+fn fmt_operand(f: &mut fmt::Formatter<'_>, e: &Expr, p: Prec) -> fmt::Result {
+    if prec(e) < p {
+        write!(f, "({})", e)
+    } else {
+        write!(f, "{}", e)
+    }
+}
+
+fn fmt_list(f: &mut fmt::Formatter<'_>, v: &[Expr]) -> fmt::Result {
+    for (i, e) in v.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        fmt_operand(f, e, Prec::Lambda)?;
+    }
+    Ok(())
+}
+
+/// Write a sum subtracting the terms with a negative sign.
+fn fmt_sum(f: &mut fmt::Formatter<'_>, terms: Vec<Expr>) -> fmt::Result {
+    for (i, t) in terms.into_iter().enumerate() {
+        match (i, split_sign(t)) {
+            (0, (Sign::Pos, t)) => fmt_operand(f, &t, Prec::Product)?,
+            (0, (Sign::Neg, t)) => {
+                write!(f, "-")?;
+                fmt_operand(f, &t, Prec::Product)?
+            }
+            (_, (Sign::Pos, t)) => {
+                write!(f, " + ")?;
+                fmt_operand(f, &t, Prec::Product)?
+            }
+            (_, (Sign::Neg, t)) => {
+                write!(f, " - ")?;
+                fmt_operand(f, &t, Prec::Product)?
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write a product as a quotient if some factors have negative exponents.
+fn fmt_product(f: &mut fmt::Formatter<'_>, e: &Expr) -> fmt::Result {
+    let fr = fraction(e.clone());
+    if fr.sign == Sign::Neg {
+        write!(f, "-")?;
+    }
+    fmt_factors(f, &fr.num)?;
+    match fr.den.len() {
+        0 => Ok(()),
+        1 => {
+            write!(f, "/")?;
+            fmt_factors(f, &fr.den)
+        }
+        _ => {
+            write!(f, "/(")?;
+            fmt_factors(f, &fr.den)?;
+            write!(f, ")")
+        }
+    }
+}
+
+fn fmt_factors(f: &mut fmt::Formatter<'_>, v: &[Expr]) -> fmt::Result {
+    if v.is_empty() {
+        return write!(f, "1");
+    }
+    for (i, e) in v.iter().enumerate() {
+        if i > 0 {
+            write!(f, "*")?;
+        }
+        match e {
+            Expr::Pow(base, exp) => {
+                fmt_operand(f, base, Prec::Atom)?;
+                write!(f, "^")?;
+                fmt_operand(f, exp, Prec::Power)?;
+            }
+            Expr::Num(n) => write!(f, "{:?}", n)?,
+            e => fmt_operand(f, e, Prec::Product)?,
+        }
+    }
+    Ok(())
+}
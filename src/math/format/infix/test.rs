@@ -0,0 +1,40 @@
+use super::super::super::utl_test::*;
+
+#[test]
+fn test_display() {
+    let tests = [
+        ("x^2 + 2x + 1", "2*x + x^2 + 1"),
+        ("1/2 - x", "-x + 1/2"),
+        ("-2x^3/3", "-2*x^3/3"),
+        ("x/(2y)", "x/(2*y)"),
+        ("1/(x + 1)^2", "1/(x + 1)^2"),
+        ("(x + 1)^(1/2) y^(-1/3)", "(x + 1)^(1/2)/y^(1/3)"),
+        ("(-2)^x (1/2)^y", "(-2)^x*(1/2)^y"),
+        ("2^x^y", "2^x^y"),
+        ("(x^y)^z", "x^(y*z)"),
+        ("e^(-x) \\sin(x)", "\\sin(x)/e^x"),
+        ("(x, -y, x \\to x^2)", "(x, -y, x \\to x^2)"),
+        ("(x, y) \\to (x - y, x y)", "(x, y) \\to (x - y, x*y)"),
+        ("\\sin", "\\sin"),
+        ("-\\infty", "-\\infty"),
+        ("x y + x^2 + y^2 = C_1", "x*y + x^2 + y^2 = C_1"),
+        ("a = (x \\to x)", "a = (x \\to x)"),
+        ("x \\to x = 1", "x \\to x = 1"),
+        ("(a = b, c)", "(a = b, c)"),
+        (
+            "\\series(\\exp(x), x, 0, 3)",
+            "\\series(x + x^2/2 + 1, x, 0, 3)",
+        ),
+        (
+            "\\series(1/\\sin(x), x, 0, 2)",
+            "\\series(x/6 + 1/x, x, 0, 2)",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        let e = parse_expr(input);
+        let s = e.to_string();
+        assert_eq!(s, expected, "{}", input);
+        assert_eq!(parse_expr(&s), e, "{}", input);
+    }
+}
@@ -0,0 +1,182 @@
+use super::{
+    args, fraction, is_operator, matrix, subscript, Prec, GREEK, OPERATORS,
+};
+use crate::math::expr::{Expr, Sign};
+use crate::math::num::Num;
+
+#[cfg(test)]
+mod test;
+
+impl Expr {
+    /// Return LaTeX markup of `self` for math mode.
+    pub fn to_latex(&self) -> String {
+        latex(self)
+    }
+}
+
+fn latex(e: &Expr) -> String {
+    match e {
+        Expr::Num(_) | Expr::Mul(_) | Expr::Pow(..) => product(e),
+        Expr::Sym(s) => symbol(s),
+        Expr::Add(a) => sum(a.clone().into_args()),
+        Expr::Vec(v) => match matrix(v) {
+            Some(rows) => {
+                let rows: Vec<_> = rows
+                    .iter()
+                    .map(|r| {
+                        r.iter().map(latex).collect::<Vec<_>>().join(" & ")
+                    })
+                    .collect();
+                format!(
+                    "\\begin{{pmatrix}} {} \\end{{pmatrix}}",
+                    rows.join(" \\\\ ")
+                )
+            }
+            None => paren(&list(v)),
+        },
+        Expr::Cmd(c) => function(c.name()),
+        Expr::Call(f, x) if f.is_cmd("\\factorial") => {
+            format!("{}!", operand(x, Prec::Atom))
+        }
+        Expr::Call(f, x) => {
+            format!("{}{}", operand(f, Prec::Atom), paren(&list(args(x))))
+        }
+        Expr::Lambda(p, b) => {
+            let p: Vec<_> = p.iter().map(|s| symbol(s)).collect();
+            let p = match p.len() {
+                1 => p[0].clone(),
+                _ => paren(&p.join(", ")),
+            };
+            format!("{} \\mapsto {}", p, operand(b, Prec::Lambda))
+        }
+        Expr::Series(s) => {
+            let o = format!("O{}", paren(&latex(&s.order_term())));
+            match s.terms() {
+                t if t.is_empty() => o,
+                t => format!("{} + {}", sum(t), o),
+            }
+        }
+        Expr::Inf(Sign::Pos) => "\\infty".to_string(),
+        Expr::Inf(Sign::Neg) => "-\\infty".to_string(),
+        Expr::Eq(l, r) => {
+            format!("{} = {}", operand(l, Prec::Sum), operand(r, Prec::Sum))
+        }
+    }
+}
+
+fn paren(s: &str) -> String {
+    format!("\\left({}\\right)", s)
+}
+
+fn operand(e: &Expr, p: Prec) -> String {
+    super::operand(e, p, latex, paren)
+}
+
+fn list(v: &[Expr]) -> String {
+    let v: Vec<_> = v.iter().map(|e| operand(e, Prec::Lambda)).collect();
+    v.join(", ")
+}
+
+/// Return a symbol with names of more than one letter set upright.
+fn symbol(s: &str) -> String {
+    let (name, sub) = subscript(s);
+    let name = match letter(name) {
+        Some(name) => name,
+        None if name.starts_with('\\') || name.chars().count() == 1 => {
+            name.to_string()
+        }
+        None => format!("\\mathrm{{{}}}", name),
+    };
+    match sub {
+        Some(sub) => {
+            let sub = letter(sub).unwrap_or_else(|| sub.to_string());
+            format!("{}_{{{}}}", name, sub)
+        }
+        None => name,
+    }
+}
+
+/// Return the command for the imaginary unit `\i` or a Greek letter.
+fn letter(s: &str) -> Option<String> {
+    if s == "\\i" {
+        return Some("\\mathrm{i}".to_string());
+    }
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => GREEK
+            .iter()
+            .find(|&&(_, g)| g == c)
+            .map(|&(name, _)| name.to_string()),
+        _ => None,
+    }
+}
+
+fn function(name: &str) -> String {
+    if OPERATORS.contains(&name) {
+        name.to_string()
+    } else {
+        format!("\\operatorname{{{}}}", name.trim_start_matches('\\'))
+    }
+}
+
+fn sum(terms: Vec<Expr>) -> String {
+    super::sum(terms, ["-", " + ", " - "], |t| operand(t, Prec::Product))
+}
+
+/// Return a product as `\frac` if some factors have negative exponents.
+fn product(e: &Expr) -> String {
+    let f = fraction(e.clone());
+    let s = if f.den.is_empty() {
+        factors(&f.num)
+    } else {
+        format!("\\frac{{{}}}{{{}}}", braced(&f.num), braced(&f.den))
+    };
+    match f.sign {
+        Sign::Pos => s,
+        Sign::Neg => format!("-{}", s),
+    }
+}
+
+/// Return the factors of a numerator or denominator, which need no
+/// parentheses if there is only one.
+fn braced(v: &[Expr]) -> String {
+    match v {
+        [e @ Expr::Add(_)] | [e @ Expr::Vec(_)] => latex(e),
+        v => factors(v),
+    }
+}
+
+/// Return the factors side by side, with `\cdot` before a number.
+fn factors(v: &[Expr]) -> String {
+    let mut s = String::new();
+    for e in v {
+        let t = match e {
+            Expr::Pow(base, exp) => power(base, exp),
+            Expr::Num(n) => format!("{:?}", n),
+            e => operand(e, Prec::Product),
+        };
+        if !s.is_empty() {
+            let digit = t.starts_with(|c: char| c.is_ascii_digit());
+            s.push_str(if digit { " \\cdot " } else { " " });
+        }
+        s.push_str(&t);
+    }
+    if s.is_empty() {
+        s.push('1');
+    }
+    s
+}
+
+fn power(base: &Expr, exp: &Expr) -> String {
+    match (base, exp) {
+        (_, Expr::Num(Num::Rat(1, 2))) => format!("\\sqrt{{{}}}", latex(base)),
+        (_, Expr::Num(Num::Rat(1, n))) => {
+            format!("\\sqrt[{}]{{{}}}", n, latex(base))
+        }
+        // Powers of functions go on the name as in `\sin^{2}(x)`.
+        (Expr::Call(f, x), _) if is_operator(f) => {
+            format!("{}^{{{}}}{}", latex(f), latex(exp), paren(&list(args(x))))
+        }
+        _ => format!("{}^{{{}}}", operand(base, Prec::Atom), latex(exp)),
+    }
+}
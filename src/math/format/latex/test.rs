@@ -0,0 +1,40 @@
+use super::super::super::utl_test::*;
+
+#[test]
+fn test_to_latex() {
+    let tests = [
+        ("x^2 - 2x + 1", "-2 x + x^{2} + 1"),
+        ("-x/2 + 3/4", r"-\frac{x}{2} + \frac{3}{4}"),
+        ("(x + 1)/(x y^2)", r"\frac{x + 1}{x y^{2}}"),
+        ("2*3^x", r"2 \cdot 3^{x}"),
+        ("x^(1/2) + (x + 1)^(1/3)", r"\sqrt{x} + \sqrt[3]{x + 1}"),
+        ("(x + 1)^(2/3)", r"\left(x + 1\right)^{\frac{2}{3}}"),
+        (
+            "\\sin(x)^2 \\cos(x)",
+            r"\cos\left(x\right) \sin^{2}\left(x\right)",
+        ),
+        ("\\factorial(n) x_1 ab", "a b x_{1} n!"),
+        ("x_{max}", "x_{max}"),
+        ("x_{10}", "x_{10}"),
+        ("x_{\\alpha}", r"x_{\alpha}"),
+        ("\\i", r"\mathrm{i}"),
+        ("θ", r"\theta"),
+        ("x_θ + Ω", r"x_{\theta} + \Omega"),
+        ("\\diff(f, x)", r"\operatorname{diff}\left(f, x\right)"),
+        (
+            "((1, x), (0, 1))",
+            r"\begin{pmatrix} 1 & x \\ 0 & 1 \end{pmatrix}",
+        ),
+        ("(1, (2, 3))", r"\left(1, \left(2, 3\right)\right)"),
+        ("(x, y) \\to x y", r"\left(x, y\right) \mapsto x y"),
+        (
+            "\\series(\\exp(x), x, 0, 2)",
+            r"1 + x + O\left(x^{2}\right)",
+        ),
+        ("-\\infty", r"-\infty"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(parse_expr(input).to_latex(), expected, "{}", input);
+    }
+}
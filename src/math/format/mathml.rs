@@ -0,0 +1,191 @@
+use super::{args, fraction, matrix, subscript, Prec, GREEK};
+use crate::math::expr::{Expr, Sign};
+use crate::math::num::Num;
+
+#[cfg(test)]
+mod test;
+
+impl Expr {
+    /// Return a MathML `<math>` element with presentation markup of
+    /// `self`.
+    pub fn to_mathml(&self) -> String {
+        format!(
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">{}</math>",
+            mathml(self)
+        )
+    }
+}
+
+fn mathml(e: &Expr) -> String {
+    match e {
+        Expr::Num(_) | Expr::Mul(_) | Expr::Pow(..) => product(e),
+        Expr::Sym(s) => symbol(s),
+        Expr::Add(a) => sum(a.clone().into_args()),
+        Expr::Vec(v) => match matrix(v) {
+            Some(rows) => {
+                let rows: String = rows
+                    .iter()
+                    .map(|r| {
+                        let r: String = r
+                            .iter()
+                            .map(|e| format!("<mtd>{}</mtd>", mathml(e)))
+                            .collect();
+                        format!("<mtr>{}</mtr>", r)
+                    })
+                    .collect();
+                paren(&format!("<mtable>{}</mtable>", rows))
+            }
+            None => paren(&list(v)),
+        },
+        Expr::Cmd(c) => function(c.name()),
+        Expr::Call(f, x) if f.is_cmd("\\factorial") => {
+            format!("<mrow>{}<mo>!</mo></mrow>", operand(x, Prec::Atom))
+        }
+        Expr::Call(f, x) => format!(
+            "<mrow>{}<mo>&#x2061;</mo>{}</mrow>",
+            operand(f, Prec::Atom),
+            paren(&list(args(x)))
+        ),
+        Expr::Lambda(p, b) => {
+            let p: Vec<_> = p.iter().map(|s| symbol(s)).collect();
+            let p = match p.len() {
+                1 => p[0].clone(),
+                _ => paren(&p.join("<mo>,</mo>")),
+            };
+            format!(
+                "<mrow>{}<mo>&#x21A6;</mo>{}</mrow>",
+                p,
+                operand(b, Prec::Lambda)
+            )
+        }
+        Expr::Series(s) => {
+            let o = format!("<mi>O</mi>{}", paren(&mathml(&s.order_term())));
+            match s.terms() {
+                t if t.is_empty() => format!("<mrow>{}</mrow>", o),
+                t => format!("<mrow>{}<mo>+</mo>{}</mrow>", sum(t), o),
+            }
+        }
+        Expr::Inf(Sign::Pos) => "<mi>&#x221E;</mi>".to_string(),
+        Expr::Inf(Sign::Neg) => {
+            "<mrow><mo>-</mo><mi>&#x221E;</mi></mrow>".to_string()
+        }
+        Expr::Eq(l, r) => format!(
+            "<mrow>{}<mo>=</mo>{}</mrow>",
+            operand(l, Prec::Sum),
+            operand(r, Prec::Sum)
+        ),
+    }
+}
+
+fn paren(s: &str) -> String {
+    format!("<mrow><mo>(</mo>{}<mo>)</mo></mrow>", s)
+}
+
+fn operand(e: &Expr, p: Prec) -> String {
+    super::operand(e, p, mathml, paren)
+}
+
+fn list(v: &[Expr]) -> String {
+    let v: Vec<_> = v.iter().map(|e| operand(e, Prec::Lambda)).collect();
+    v.join("<mo>,</mo>")
+}
+
+fn symbol(s: &str) -> String {
+    match subscript(s) {
+        (name, Some(sub)) => {
+            format!("<msub>{}{}</msub>", identifier(name), token(sub))
+        }
+        (name, None) => identifier(name),
+    }
+}
+
+/// Return `s` as a number or an identifier.
+fn token(s: &str) -> String {
+    if s.chars().all(|c| c.is_ascii_digit()) {
+        format!("<mn>{}</mn>", s)
+    } else {
+        identifier(s)
+    }
+}
+
+/// Return an identifier with Greek letters as characters.
+fn identifier(s: &str) -> String {
+    match GREEK.iter().find(|&&(name, _)| name == s) {
+        Some(&(_, c)) => format!("<mi>&#x{:X};</mi>", c as u32),
+        None => format!("<mi>{}</mi>", s.trim_start_matches('\\')),
+    }
+}
+
+fn function(name: &str) -> String {
+    format!("<mi>{}</mi>", name.trim_start_matches('\\'))
+}
+
+fn sum(terms: Vec<Expr>) -> String {
+    let signs = ["<mo>-</mo>", "<mo>+</mo>", "<mo>-</mo>"];
+    let s = super::sum(terms, signs, |t| operand(t, Prec::Product));
+    format!("<mrow>{}</mrow>", s)
+}
+
+/// Return a product as `<mfrac>` if some factors have negative exponents.
+fn product(e: &Expr) -> String {
+    let f = fraction(e.clone());
+    let s = if f.den.is_empty() {
+        factors(&f.num)
+    } else {
+        format!("<mfrac>{}{}</mfrac>", part(&f.num), part(&f.den))
+    };
+    match f.sign {
+        Sign::Pos => s,
+        Sign::Neg => format!("<mrow><mo>-</mo>{}</mrow>", s),
+    }
+}
+
+/// Return the factors of a numerator or denominator, which need no
+/// parentheses if there is only one.
+fn part(v: &[Expr]) -> String {
+    match v {
+        [e @ Expr::Add(_)] => mathml(e),
+        v => factors(v),
+    }
+}
+
+/// Return the factors joined by invisible times, or by a dot before a
+/// number.
+fn factors(v: &[Expr]) -> String {
+    let v: Vec<_> = v
+        .iter()
+        .map(|e| match e {
+            Expr::Pow(base, exp) => power(base, exp),
+            Expr::Num(n) => format!("<mn>{:?}</mn>", n),
+            e => operand(e, Prec::Product),
+        })
+        .collect();
+    match v.len() {
+        0 => "<mn>1</mn>".to_string(),
+        1 => v[0].clone(),
+        _ => {
+            let mut s = v[0].clone();
+            for t in &v[1..] {
+                if t.starts_with("<mn>") {
+                    s.push_str("<mo>&#x22C5;</mo>");
+                } else {
+                    s.push_str("<mo>&#x2062;</mo>");
+                }
+                s.push_str(t);
+            }
+            format!("<mrow>{}</mrow>", s)
+        }
+    }
+}
+
+fn power(base: &Expr, exp: &Expr) -> String {
+    match exp {
+        Expr::Num(Num::Rat(1, 2)) => format!("<msqrt>{}</msqrt>", mathml(base)),
+        Expr::Num(Num::Rat(1, n)) => {
+            format!("<mroot>{}<mn>{}</mn></mroot>", mathml(base), n)
+        }
+        _ => {
+            format!("<msup>{}{}</msup>", operand(base, Prec::Atom), mathml(exp))
+        }
+    }
+}
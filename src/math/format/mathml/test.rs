@@ -0,0 +1,57 @@
+use super::super::super::utl_test::*;
+
+#[test]
+fn test_to_mathml() {
+    let tests = [
+        (
+            "x^2 - 2x + 1",
+            "<mrow><mo>-</mo><mrow><mn>2</mn><mo>&#x2062;</mo><mi>x</mi></mrow><mo>+</mo><msup><mi>x</mi><mn>2</mn></msup><mo>+</mo><mn>1</mn></mrow>",
+        ),
+        (
+            "(x + 1)/(2y)",
+            "<mfrac><mrow><mi>x</mi><mo>+</mo><mn>1</mn></mrow><mrow><mn>2</mn><mo>&#x2062;</mo><mi>y</mi></mrow></mfrac>",
+        ),
+        (
+            "2*3^x",
+            "<mrow><mn>2</mn><mo>&#x2062;</mo><msup><mn>3</mn><mi>x</mi></msup></mrow>",
+        ),
+        (
+            "x^(1/2) + y^(1/3)",
+            "<mrow><msqrt><mi>x</mi></msqrt><mo>+</mo><mroot><mi>y</mi><mn>3</mn></mroot></mrow>",
+        ),
+        (
+            "\\sin(x_1)",
+            "<mrow><mi>sin</mi><mo>&#x2061;</mo><mrow><mo>(</mo><msub><mi>x</mi><mn>1</mn></msub><mo>)</mo></mrow></mrow>",
+        ),
+        (
+            "\\factorial(n + 1)",
+            "<mrow><mrow><mo>(</mo><mrow><mi>n</mi><mo>+</mo><mn>1</mn></mrow><mo>)</mo></mrow><mo>!</mo></mrow>",
+        ),
+        (
+            "((1, x), (0, 1))",
+            "<mrow><mo>(</mo><mtable><mtr><mtd><mn>1</mn></mtd><mtd><mi>x</mi></mtd></mtr><mtr><mtd><mn>0</mn></mtd><mtd><mn>1</mn></mtd></mtr></mtable><mo>)</mo></mrow>",
+        ),
+        (
+            "x \\to x^2",
+            "<mrow><mi>x</mi><mo>&#x21A6;</mo><msup><mi>x</mi><mn>2</mn></msup></mrow>",
+        ),
+        ("\\infty", "<mi>&#x221E;</mi>"),
+        ("\\pi", "<mi>&#x3C0;</mi>"),
+        (
+            "x_{max} \\Omega",
+            "<mrow><mi>&#x3A9;</mi><mo>&#x2062;</mo><msub><mi>x</mi><mi>max</mi></msub></mrow>",
+        ),
+        (
+            "x_{10} y_{\\alpha}",
+            "<mrow><msub><mi>x</mi><mn>10</mn></msub><mo>&#x2062;</mo><msub><mi>y</mi><mi>&#x3B1;</mi></msub></mrow>",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        let expected = format!(
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">{}</math>",
+            expected
+        );
+        assert_eq!(parse_expr(input).to_mathml(), expected, "{}", input);
+    }
+}
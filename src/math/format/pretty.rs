@@ -0,0 +1,290 @@
+use super::{args, fraction, prec, split_sign, Prec};
+use crate::math::expr::{Expr, Sign};
+use crate::math::num::Num;
+
+#[cfg(test)]
+mod test;
+
+/// Characters drawing the operators, fraction bars and parentheses.
+struct Style {
+    bar: char,
+    /// Parenthesis of one line, then the top, middle and bottom of a
+    /// taller one.
+    open: [char; 4],
+    close: [char; 4],
+    times: &'static str,
+    to: &'static str,
+    sqrt: &'static str,
+    /// Whether an atom under the root sign goes without parentheses.
+    bare_root: bool,
+    infinity: &'static str,
+}
+
+const ASCII: Style = Style {
+    bar: '-',
+    open: ['(', '/', '|', '\\'],
+    close: [')', '\\', '|', '/'],
+    times: "*",
+    to: " -> ",
+    sqrt: "sqrt",
+    bare_root: false,
+    infinity: "oo",
+};
+
+const UNICODE: Style = Style {
+    bar: '─',
+    open: ['(', '⎛', '⎜', '⎝'],
+    close: [')', '⎞', '⎟', '⎠'],
+    times: "⋅",
+    to: " ↦ ",
+    sqrt: "√",
+    bare_root: true,
+    infinity: "∞",
+};
+
+/// Return `e` drawn on several lines with stacked fractions and raised
+/// exponents, using Unicode box drawing characters if `unicode` is set.
+pub fn pretty(e: &Expr, unicode: bool) -> String {
+    let style = if unicode { &UNICODE } else { &ASCII };
+    let b = Printer { style }.expr(e);
+    let lines: Vec<_> = b.lines.iter().map(|l| l.trim_end()).collect();
+    lines.join("\n")
+}
+
+/// Lines of text of equal width with the index of the line which is
+/// aligned with the text around it.
+struct Block {
+    lines: Vec<String>,
+    base: usize,
+    width: usize,
+}
+
+impl Block {
+    fn text(s: &str) -> Block {
+        Block {
+            lines: vec![s.to_string()],
+            base: 0,
+            width: s.chars().count(),
+        }
+    }
+
+    fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Return line `i` of a block whose base line is `base`, blank if the
+    /// block does not reach it.
+    fn line(&self, i: usize, base: usize) -> String {
+        match (i + self.base).checked_sub(base) {
+            Some(j) if j < self.height() => self.lines[j].clone(),
+            _ => " ".repeat(self.width),
+        }
+    }
+
+    /// Put `other` on the right, aligning the base lines.
+    fn beside(self, other: Block) -> Block {
+        let base = self.base.max(other.base);
+        let below =
+            (self.height() - self.base).max(other.height() - other.base);
+        let lines = (0..base + below)
+            .map(|i| self.line(i, base) + &other.line(i, base))
+            .collect();
+        Block {
+            lines,
+            base,
+            width: self.width + other.width,
+        }
+    }
+
+    /// Put `num` above `den` separated by a bar on the base line.
+    fn over(num: Block, den: Block, bar: char) -> Block {
+        let width = num.width.max(den.width);
+        let center = |b: Block| {
+            let left = (width - b.width) / 2;
+            let right = width - b.width - left;
+            b.lines
+                .into_iter()
+                .map(move |l| " ".repeat(left) + &l + &" ".repeat(right))
+        };
+        let base = num.height();
+        let mut lines: Vec<_> = center(num).collect();
+        lines.push(bar.to_string().repeat(width));
+        lines.extend(center(den));
+        Block { lines, base, width }
+    }
+
+    /// Put `exp` above the right end of `self`.
+    fn raise(self, exp: Block) -> Block {
+        let pad = " ".repeat(self.width);
+        let mut lines: Vec<_> =
+            exp.lines.iter().map(|l| pad.clone() + l).collect();
+        let pad = " ".repeat(exp.width);
+        lines.extend(self.lines.iter().map(|l| l.clone() + &pad));
+        Block {
+            lines,
+            base: exp.height() + self.base,
+            width: self.width + exp.width,
+        }
+    }
+
+    /// Enclose in parentheses as tall as the block.
+    fn paren(self, style: &Style) -> Block {
+        let side = |c: &[char; 4]| {
+            let lines = match self.height() {
+                1 => vec![c[0].to_string()],
+                h => (0..h)
+                    .map(|i| match i {
+                        0 => c[1],
+                        i if i == h - 1 => c[3],
+                        _ => c[2],
+                    })
+                    .map(String::from)
+                    .collect(),
+            };
+            Block {
+                lines,
+                base: self.base,
+                width: 1,
+            }
+        };
+        let (open, close) = (side(&style.open), side(&style.close));
+        open.beside(self).beside(close)
+    }
+}
+
+struct Printer<'a> {
+    style: &'a Style,
+}
+
+impl Printer<'_> {
+    fn expr(&self, e: &Expr) -> Block {
+        match e {
+            Expr::Num(_) | Expr::Mul(_) | Expr::Pow(..) => self.product(e),
+            Expr::Sym(s) => Block::text(name(s)),
+            Expr::Add(a) => self.sum(a.clone().into_args()),
+            Expr::Vec(v) => self.list(v).paren(self.style),
+            Expr::Cmd(c) => Block::text(name(c.name())),
+            Expr::Call(f, x) => self
+                .operand(f, Prec::Atom)
+                .beside(self.list(args(x)).paren(self.style)),
+            Expr::Lambda(p, b) => {
+                let p: Vec<_> = p.iter().map(|s| name(s)).collect();
+                let p = match p.len() {
+                    1 => Block::text(p[0]),
+                    _ => Block::text(&p.join(", ")).paren(self.style),
+                };
+                p.beside(Block::text(self.style.to))
+                    .beside(self.operand(b, Prec::Lambda))
+            }
+            Expr::Series(s) => {
+                let o = self.expr(&s.order_term()).paren(self.style);
+                let o = Block::text("O").beside(o);
+                match s.terms() {
+                    t if t.is_empty() => o,
+                    t => self.sum(t).beside(Block::text(" + ")).beside(o),
+                }
+            }
+            Expr::Inf(Sign::Pos) => Block::text(self.style.infinity),
+            Expr::Inf(Sign::Neg) => {
+                Block::text(&format!("-{}", self.style.infinity))
+            }
+            Expr::Eq(l, r) => self
+                .operand(l, Prec::Sum)
+                .beside(Block::text(" = "))
+                .beside(self.operand(r, Prec::Sum)),
+        }
+    }
+
+    /// Draw `e`, in parentheses if it binds less tightly than `p`.
+    fn operand(&self, e: &Expr, p: Prec) -> Block {
+        let b = self.expr(e);
+        if prec(e) < p {
+            b.paren(self.style)
+        } else {
+            b
+        }
+    }
+
+    /// Draw the elements of `v` separated by commas.
+    fn list(&self, v: &[Expr]) -> Block {
+        let mut i = v.iter().map(|e| self.operand(e, Prec::Lambda));
+        let first = i.next().unwrap_or_else(|| Block::text(""));
+        i.fold(first, |b, e| b.beside(Block::text(", ")).beside(e))
+    }
+
+    /// Draw a sum subtracting the terms with a negative sign.
+    fn sum(&self, terms: Vec<Expr>) -> Block {
+        let mut out: Option<Block> = None;
+        for t in terms {
+            let (sign, t) = split_sign(t);
+            let t = self.operand(&t, Prec::Product);
+            out = Some(match (out, sign) {
+                (None, Sign::Pos) => t,
+                (None, Sign::Neg) if t.height() > 1 => {
+                    Block::text("- ").beside(t)
+                }
+                (None, Sign::Neg) => Block::text("-").beside(t),
+                (Some(b), Sign::Pos) => b.beside(Block::text(" + ")).beside(t),
+                (Some(b), Sign::Neg) => b.beside(Block::text(" - ")).beside(t),
+            });
+        }
+        out.unwrap_or_else(|| Block::text("0"))
+    }
+
+    /// Draw a product with the factors of negative exponents below a
+    /// fraction bar.
+    fn product(&self, e: &Expr) -> Block {
+        let f = fraction(e.clone());
+        let b = if f.den.is_empty() {
+            self.factors(&f.num)
+        } else {
+            let num = self.fraction_side(&f.num);
+            Block::over(num, self.fraction_side(&f.den), self.style.bar)
+        };
+        match f.sign {
+            Sign::Pos => b,
+            // Keep the sign apart from an ASCII fraction bar.
+            Sign::Neg if b.height() > 1 => Block::text("- ").beside(b),
+            Sign::Neg => Block::text("-").beside(b),
+        }
+    }
+
+    /// Draw the numerator or denominator of a fraction, where the bar
+    /// separates a single sum without parentheses.
+    fn fraction_side(&self, v: &[Expr]) -> Block {
+        match v {
+            [e @ Expr::Add(_)] => self.expr(e),
+            v => self.factors(v),
+        }
+    }
+
+    fn factors(&self, v: &[Expr]) -> Block {
+        let mut i = v.iter().map(|e| match e {
+            Expr::Pow(base, exp) => self.power(base, exp),
+            Expr::Num(n) => Block::text(&format!("{:?}", n)),
+            e => self.operand(e, Prec::Product),
+        });
+        let first = i.next().unwrap_or_else(|| Block::text("1"));
+        i.fold(first, |b, e| {
+            b.beside(Block::text(self.style.times)).beside(e)
+        })
+    }
+
+    fn power(&self, base: &Expr, exp: &Expr) -> Block {
+        if *exp == Expr::Num(Num::rational(1, 2)) {
+            let b = self.expr(base);
+            let b = match prec(base) {
+                Prec::Atom if b.height() == 1 && self.style.bare_root => b,
+                _ => b.paren(self.style),
+            };
+            Block::text(self.style.sqrt).beside(b)
+        } else {
+            self.operand(base, Prec::Atom).raise(self.expr(exp))
+        }
+    }
+}
+
+/// Return the name of a symbol or builtin without the backslash.
+fn name(s: &str) -> &str {
+    s.strip_prefix('\\').unwrap_or(s)
+}
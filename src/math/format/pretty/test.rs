@@ -0,0 +1,49 @@
+use super::super::super::utl_test::*;
+use super::*;
+
+#[test]
+fn test_pretty() {
+    let tests = [
+        ("x - 1", "x - 1"),
+        ("2x + x^2 + 1", "       2\n2*x + x  + 1"),
+        ("-x/2", "  x\n- -\n  2"),
+        ("(x + 1)/(x - 1)", "x + 1\n-----\nx - 1"),
+        ("(x^2 + 1)/(y - 1)", " 2\nx  + 1\n------\ny - 1"),
+        ("(x + 1)/(y (x - 1))", "  x + 1\n---------\ny*(x - 1)"),
+        ("y / x^2", "y\n--\n 2\nx"),
+        ("-x - 1/x", "     1\n-x - -\n     x"),
+        ("-x/2 - y", "  x\n- - - y\n  2"),
+        ("(x + 1)^(1/x)", "       1\n       -\n       x\n(x + 1)"),
+        ("2^(x^2)", "  2\n x\n2"),
+        (
+            "\\sin(x)^2 / (1 + y)^(1/2)",
+            "        2\n  sin(x)\n-----------\nsqrt(y + 1)",
+        ),
+        ("(x, y/2)", "/   y\\\n|x, -|\n\\   2/"),
+        ("(x, y) \\to x + y", "(x, y) -> x + y"),
+        (
+            "\\series(\\exp(x), x, 0, 3)",
+            "         2\n        x     / 3\\\n1 + x + -- + O\\x /\n        2",
+        ),
+        ("-\\infty", "-oo"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(pretty(&parse_expr(input), false), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_pretty_unicode() {
+    let tests = [
+        ("-x/2", "  x\n- ─\n  2"),
+        ("x^(1/2) + (x + 1)^(1/2)", "√x + √(x + 1)"),
+        ("(x, y/2)", "⎛   y⎞\n⎜x, ─⎟\n⎝   2⎠"),
+        ("x \\to 2x", "x ↦ 2⋅x"),
+        ("\\infty", "∞"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(pretty(&parse_expr(input), true), expected, "{}", input);
+    }
+}
@@ -0,0 +1,184 @@
+use super::builtin::{Cmd, Function};
+use super::error::EvalError;
+use super::expr::{Expr, ONE, ZERO};
+use super::num::Num;
+use super::series;
+
+#[cfg(test)]
+mod test;
+
+pub const EXP: Function = Function {
+    name: "\\exp",
+    arity: 1..=1,
+    help: "\\exp(x): exponential function",
+    eval: lib_exp,
+};
+pub const LN: Function = Function {
+    name: "\\ln",
+    arity: 1..=1,
+    help: "\\ln(x): natural logarithm",
+    eval: lib_ln,
+};
+pub const SIN: Function = Function {
+    name: "\\sin",
+    arity: 1..=1,
+    help: "\\sin(x): sine",
+    eval: lib_sin,
+};
+pub const COS: Function = Function {
+    name: "\\cos",
+    arity: 1..=1,
+    help: "\\cos(x): cosine",
+    eval: lib_cos,
+};
+pub const FACTORIAL: Function = Function {
+    name: "\\factorial",
+    arity: 1..=1,
+    help: "\\factorial(n): factorial of a non-negative integer",
+    eval: lib_factorial,
+};
+pub const CMD_EXP: Expr = Expr::Cmd(Cmd(&EXP));
+pub const CMD_LN: Expr = Expr::Cmd(Cmd(&LN));
+pub const CMD_SIN: Expr = Expr::Cmd(Cmd(&SIN));
+pub const CMD_COS: Expr = Expr::Cmd(Cmd(&COS));
+pub const CMD_FACTORIAL: Expr = Expr::Cmd(Cmd(&FACTORIAL));
+
+pub fn exp(x: Expr) -> Expr {
+    match x {
+        ZERO => ONE,
+        Expr::Call(f, x) if f.is_cmd("\\ln") => *x,
+        Expr::Mul(m) => match m.clone().into_args().as_slice() {
+            // exp(c ln(x)) = x^c
+            [c @ Expr::Num(_), Expr::Call(f, x)] if f.is_cmd("\\ln") => {
+                Expr::pow(*x.clone(), c.clone())
+            }
+            _ => Expr::unevaluated_call(CMD_EXP, Expr::Mul(m)),
+        },
+        x => Expr::unevaluated_call(CMD_EXP, x),
+    }
+}
+
+/// Merge the exponentials in every product into a single exponential.
+pub fn combine_exp(e: Expr) -> Expr {
+    match e {
+        Expr::Add(a) => Expr::sum(a.into_args().into_iter().map(combine_exp)),
+        Expr::Mul(m) => {
+            let mut arg = ZERO;
+            let mut rest = Vec::new();
+            for e in m.into_args() {
+                match combine_exp(e) {
+                    Expr::Call(f, x) if f.is_cmd("\\exp") => arg += *x,
+                    e => rest.push(e),
+                }
+            }
+            rest.push(exp(arg));
+            Expr::prod(rest)
+        }
+        Expr::Pow(b, n) => match (*b, *n) {
+            (Expr::Call(f, x), n @ Expr::Num(_)) if f.is_cmd("\\exp") => {
+                exp(n * *x)
+            }
+            (b, n) => Expr::pow(combine_exp(b), n),
+        },
+        Expr::Call(f, x) => Expr::call(*f, combine_exp(*x)),
+        e => e,
+    }
+}
+
+pub fn ln(x: Expr) -> Expr {
+    match x {
+        ONE => ZERO,
+        Expr::Call(f, x) if f.is_cmd("\\exp") => *x,
+        x => Expr::unevaluated_call(CMD_LN, x),
+    }
+}
+
+pub fn sin(x: Expr) -> Expr {
+    match x {
+        ZERO => ZERO,
+        x => Expr::unevaluated_call(CMD_SIN, x),
+    }
+}
+
+pub fn cos(x: Expr) -> Expr {
+    match x {
+        ZERO => ONE,
+        x => Expr::unevaluated_call(CMD_COS, x),
+    }
+}
+
+pub fn factorial(x: Expr) -> Expr {
+    if let Expr::Num(Num::Int(n)) = x {
+        if n >= 0 {
+            if let Some(r) = (1..=n).try_fold(1i32, |r, i| r.checked_mul(i)) {
+                return Expr::int(r);
+            }
+        }
+    }
+    Expr::unevaluated_call(CMD_FACTORIAL, x)
+}
+
+/// Return the derivative of the elementary function `name` at `x`,
+/// or `None` if `name` is not an elementary function.
+pub fn derivative(name: &str, x: Expr) -> Option<Expr> {
+    match name {
+        "\\exp" => Some(exp(x)),
+        "\\ln" => Some(Expr::pow(x, Expr::int(-1))),
+        "\\sin" => Some(cos(x)),
+        "\\cos" => Some(Expr::int(-1) * sin(x)),
+        _ => None,
+    }
+}
+
+pub fn is_elementary(name: &str) -> bool {
+    matches!(name, "\\exp" | "\\ln" | "\\sin" | "\\cos")
+}
+
+fn lib_apply(
+    f: fn(Expr) -> Expr,
+    cmd: Expr,
+    args: Vec<Expr>,
+) -> Result<Expr, EvalError> {
+    match args.into_iter().next().unwrap_or_default() {
+        Expr::Vec(_) | Expr::Eq(..) => {
+            Err(EvalError::type_mismatch("arguments error"))
+        }
+        Expr::Series(s) => series::apply(cmd, *s),
+        x => Ok(f(x)),
+    }
+}
+
+pub fn lib_exp(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    lib_apply(exp, CMD_EXP, args)
+}
+
+pub fn lib_ln(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    match args.first() {
+        Some(&ZERO) => Err(EvalError::domain("logarithm of zero")),
+        _ => lib_apply(ln, CMD_LN, args),
+    }
+}
+
+pub fn lib_sin(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    lib_apply(sin, CMD_SIN, args)
+}
+
+pub fn lib_cos(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    lib_apply(cos, CMD_COS, args)
+}
+
+pub fn lib_factorial(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    match args.into_iter().next().unwrap_or_default() {
+        Expr::Vec(_) | Expr::Series(_) | Expr::Eq(..) => {
+            Err(EvalError::type_mismatch("arguments error"))
+        }
+        Expr::Num(Num::Int(n)) if n < 0 => {
+            Err(EvalError::domain("factorial of a negative integer"))
+        }
+        x @ Expr::Num(Num::Int(_)) => match factorial(x) {
+            r @ Expr::Num(_) => Ok(r),
+            _ => Err(EvalError::overflow("integer overflow")),
+        },
+        x => Ok(factorial(x)),
+    }
+}
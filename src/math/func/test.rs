@@ -0,0 +1,21 @@
+use super::super::utl_test::*;
+
+#[test]
+fn test_func() {
+    let tests = [
+        (r"\exp(0)", "1"),
+        (r"\ln(1)", "0"),
+        (r"\sin(0)", "0"),
+        (r"\cos(0)", "1"),
+        (r"\exp(\ln(x))", "x"),
+        (r"\ln(\exp(x))", "x"),
+        (r"\sin(x) + \sin(x)", r"(2 * \sin(x))"),
+        (r"\factorial(5)", "120"),
+        (r"\factorial(n)", r"\factorial(n)"),
+        (r"\exp(1, 2)", "arguments error"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
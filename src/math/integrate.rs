@@ -0,0 +1,104 @@
+use super::diff::partial;
+use super::expr::{Expr, ONE};
+use super::func;
+use super::num::Num;
+use super::poly;
+
+#[cfg(test)]
+mod test;
+
+/// Return the coefficient of `var` if `e` is linear in `var`.
+fn linear(e: &Expr, var: &str) -> Option<Expr> {
+    match poly::coeffs(e, var) {
+        Some(ref p) if p.len() == 2 => Some(p[1].clone()),
+        _ => None,
+    }
+}
+
+/// Return an antiderivative of `e` with respect to `var` treating every
+/// other symbol as a constant, or `None` if no rule applies.
+pub fn integrate(e: &Expr, var: &str) -> Option<Expr> {
+    let x = Expr::Sym(var.to_string());
+    if !e.has_sym(var) {
+        return Some(e.clone() * x);
+    }
+    match e {
+        Expr::Sym(_) => {
+            Some(Expr::Num(Num::rational(1, 2)) * Expr::pow(x, Expr::int(2)))
+        }
+        Expr::Add(a) => {
+            let mut r = Vec::new();
+            for e in a.clone().into_args() {
+                r.push(integrate(&e, var)?);
+            }
+            Some(Expr::sum(r))
+        }
+        Expr::Mul(m) => {
+            let (c, f): (Vec<Expr>, Vec<Expr>) = m
+                .clone()
+                .into_args()
+                .into_iter()
+                .partition(|e| !e.has_sym(var));
+            let r = match f.as_slice() {
+                [f] => integrate(f, var)?,
+                _ => by_parts(f, var)?,
+            };
+            Some(Expr::prod(c) * r)
+        }
+        Expr::Pow(base, exp) => match (&**base, &**exp) {
+            // exp(u)^n = exp(n u)
+            (Expr::Call(f, u), n) if f.is_cmd("\\exp") => {
+                integrate(&func::exp(n.clone() * *u.clone()), var)
+            }
+            (base, exp) if !exp.has_sym(var) => {
+                let a = linear(base, var)?;
+                if *exp == Expr::int(-1) {
+                    Some(func::ln(base.clone()) / a)
+                } else {
+                    let exp = exp.clone() + ONE;
+                    Some(Expr::pow(base.clone(), exp.clone()) / (a * exp))
+                }
+            }
+            (base, exp) if !base.has_sym(var) => {
+                let a = linear(exp, var)?;
+                Some(e.clone() / (a * func::ln(base.clone())))
+            }
+            _ => None,
+        },
+        Expr::Call(f, u) => {
+            let a = linear(u, var)?;
+            let u = *u.clone();
+            match f.cmd_name()? {
+                "\\exp" => Some(func::exp(u) / a),
+                "\\sin" => Some(Expr::int(-1) * func::cos(u) / a),
+                "\\cos" => Some(func::sin(u) / a),
+                "\\ln" => Some((u.clone() * func::ln(u.clone()) - u) / a),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Integrate a product of a polynomial and a factor with a known
+/// antiderivative `G` by `int P g = P G - int P' G`.
+fn by_parts(factors: Vec<Expr>, var: &str) -> Option<Expr> {
+    let (p, g): (Vec<Expr>, Vec<Expr>) = factors
+        .into_iter()
+        .partition(|e| poly::coeffs(e, var).is_some());
+    let p = Expr::prod(p);
+    let g = func::combine_exp(Expr::prod(g));
+    if g == ONE {
+        return integrate(&p.expand(), var);
+    }
+    if p == ONE {
+        return match g {
+            Expr::Mul(_) => None,
+            g => integrate(&g, var),
+        };
+    }
+    let g = integrate(&g, var)?;
+    let dp = partial(p.clone(), var);
+    let rest = integrate(&(dp * g.clone()).expand(), var)?;
+    Some(p * g - rest)
+}
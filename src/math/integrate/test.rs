@@ -0,0 +1,26 @@
+use super::super::utl_test::*;
+use super::integrate;
+
+#[test]
+fn test_integrate() {
+    let tests = [
+        ("3", "(3 * x)"),
+        ("x^2 + a", "((a * x) + (1/3 * (x ^ 3)))"),
+        ("x^-1", "\\ln(x)"),
+        ("(2 x + 1)^2", "(1/6 * (((2 * x) + 1) ^ 3))"),
+        ("\\exp(2 x)", "(1/2 * \\exp((2 * x)))"),
+        (
+            "\\sin(x) + \\cos(3 x)",
+            "((-1 * \\cos(x)) + (1/3 * \\sin((3 * x))))",
+        ),
+        ("x * \\exp(x)", "((x * \\exp(x)) + (-1 * \\exp(x)))"),
+        ("x * \\sin(x)", "((-1 * x * \\cos(x)) + \\sin(x))"),
+        ("\\ln(x)", "((-1 * x) + (x * \\ln(x)))"),
+    ];
+
+    for (input, expected) in tests {
+        let expr = integrate(&parse_expr(input), "x");
+        assert_eq!(format!("{:?}", expr.unwrap()), expected);
+    }
+    assert_eq!(integrate(&parse_expr("\\exp(x^2)"), "x"), None);
+}
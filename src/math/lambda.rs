@@ -0,0 +1,140 @@
+use super::builtin::{Cmd, EnvFunction};
+use super::error::EvalError;
+use super::expr::Expr;
+use super::num::Num;
+use crate::interpreter::environment::Environment;
+
+#[cfg(test)]
+mod test;
+
+pub const MAP: EnvFunction = EnvFunction {
+    name: "\\map",
+    arity: 2..=2,
+    help: "\\map(f, (x, y, ...)): list of f(x), f(y), ...",
+    eval: lib_map,
+};
+pub const APPLY: EnvFunction = EnvFunction {
+    name: "\\apply",
+    arity: 2..=2,
+    help: "\\apply(f, (x, y, ...)): f(x, y, ...)",
+    eval: lib_apply,
+};
+pub const COMPOSE: EnvFunction = EnvFunction {
+    name: "\\compose",
+    arity: 2..=usize::MAX,
+    help: "\\compose(f, g, ...): function x -> f(g(...(x)))",
+    eval: lib_compose,
+};
+pub const NEST: EnvFunction = EnvFunction {
+    name: "\\nest",
+    arity: 3..=3,
+    help: "\\nest(f, x, n): f applied n times to x",
+    eval: lib_nest,
+};
+pub const CMD_MAP: Expr = Expr::Cmd(Cmd(&MAP));
+pub const CMD_APPLY: Expr = Expr::Cmd(Cmd(&APPLY));
+pub const CMD_COMPOSE: Expr = Expr::Cmd(Cmd(&COMPOSE));
+pub const CMD_NEST: Expr = Expr::Cmd(Cmd(&NEST));
+
+/// Maximum number of times `\nest` applies a function.
+const MAX_NEST: i32 = 10000;
+
+/// Names tried for the parameter of a composed function.
+const PARAMS: [&str; 7] = ["x", "y", "z", "t", "u", "v", "w"];
+
+fn is_callable(f: &Expr) -> bool {
+    match f {
+        Expr::Cmd(..) | Expr::Lambda(..) => true,
+        Expr::Mul(m) => m.clone().into_args().iter().any(is_callable),
+        _ => false,
+    }
+}
+
+/// Apply `f` to each element of `list`.
+pub fn map(
+    f: Expr,
+    list: Vec<Expr>,
+    env: &Environment,
+) -> Result<Expr, EvalError> {
+    let v = list
+        .into_iter()
+        .map(|e| Expr::call_in(f.clone(), e, env))
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.context(MAP.name))?;
+    Ok(Expr::Vec(v))
+}
+
+/// Return the function `x -> f(g(x))`.
+pub fn compose(f: Expr, g: Expr) -> Expr {
+    let (params, inner) = match g {
+        Expr::Lambda(params, body) => (params, *body),
+        g => {
+            // Pick a parameter which is not captured by either function.
+            let p = PARAMS
+                .iter()
+                .find(|p| !f.has_sym(p) && !g.has_sym(p))
+                .map_or_else(|| String::from("\\_x"), |p| p.to_string());
+            let inner = Expr::call(g, Expr::Sym(p.clone()));
+            (vec![p], inner)
+        }
+    };
+    Expr::Lambda(params, Box::new(Expr::call(f, inner)))
+}
+
+/// Apply `f` to `x` repeatedly `n` times.
+pub fn nest(
+    f: Expr,
+    x: Expr,
+    n: u32,
+    env: &Environment,
+) -> Result<Expr, EvalError> {
+    (0..n)
+        .try_fold(x, |x, _| Expr::call_in(f.clone(), x, env))
+        .map_err(|e| e.context(NEST.name))
+}
+
+pub fn lib_map(args: Vec<Expr>, env: &Environment) -> Result<Expr, EvalError> {
+    if let [f, Expr::Vec(list)] = args.as_slice() {
+        if is_callable(f) {
+            return map(f.clone(), list.clone(), env);
+        }
+    }
+    Err(EvalError::type_mismatch("arguments error"))
+}
+
+pub fn lib_apply(
+    args: Vec<Expr>,
+    env: &Environment,
+) -> Result<Expr, EvalError> {
+    if let [f, args] = args.as_slice() {
+        if is_callable(f) {
+            return Expr::call_in(f.clone(), args.clone(), env)
+                .map_err(|e| e.context(APPLY.name));
+        }
+    }
+    Err(EvalError::type_mismatch("arguments error"))
+}
+
+pub fn lib_compose(
+    args: Vec<Expr>,
+    _: &Environment,
+) -> Result<Expr, EvalError> {
+    if !args.iter().all(is_callable) {
+        return Err(EvalError::type_mismatch("arguments error"));
+    }
+    let mut v = args.into_iter().rev();
+    let g = v.next().unwrap();
+    Ok(v.fold(g, |g, f| compose(f, g)))
+}
+
+pub fn lib_nest(args: Vec<Expr>, env: &Environment) -> Result<Expr, EvalError> {
+    if let [f, x, Expr::Num(Num::Int(n))] = args.as_slice() {
+        if is_callable(f) && *n >= 0 {
+            if *n > MAX_NEST {
+                return Err(EvalError::domain("too many iterations"));
+            }
+            return nest(f.clone(), x.clone(), *n as u32, env);
+        }
+    }
+    Err(EvalError::type_mismatch("arguments error"))
+}
@@ -0,0 +1,53 @@
+use super::super::utl_test::*;
+
+#[test]
+fn test_lambda() {
+    let tests = [
+        (r"x \to x^2", r"(x \to (x ^ 2))"),
+        (r"(x \to x^2)(3)", "9"),
+        (r"(x, y) \to x y", r"((x, y) \to (x * y))"),
+        (r"((x, y) \to x - y)(y, 1)", "(y + -1)"),
+        (r"x \to y \to x + y", r"(x \to (y \to (x + y)))"),
+        (r"(x \to y \to x + y)(1)", r"(y \to (y + 1))"),
+        (r"(x \to y \to x + y)(1)(2)", "3"),
+        (r"(x \to y \to x + y)(y)(2)", "(y + 2)"),
+        (r"(x \to y \to x + y)(y)", r"(y_1 \to (y + y_1))"),
+        (
+            r"(x \to (y, y_1) \to x y y_1)(y)",
+            r"((y_2, y_1) \to (y * y_1 * y_2))",
+        ),
+        (r"\diff((x \to x^3)(t), t)", "(3 * (t ^ 2))"),
+        (r"(1 \to 2)", "invalid lambda parameters"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_higher_order() {
+    let tests = [
+        (r"\map(x \to x^2, (1, 2, 3))", "(1, 4, 9)"),
+        (r"\map(\exp, (0, \ln(a)))", "(1, a)"),
+        (r"\apply((x, y) \to x / y, (6, 4))", "3/2"),
+        (r"\apply(\sin, 0)", "0"),
+        (
+            r"\compose(x \to x + 1, x \to 2 x)",
+            r"(x \to ((2 * x) + 1))",
+        ),
+        (r"\compose(\exp, \ln)", r"(x \to x)"),
+        (r"\compose(t \to t + x, \sin)", r"(y \to (x + \sin(y)))"),
+        (r"\compose(x \to x + 1, x \to x^2, x \to 2 x)(3)", "37"),
+        (r"\nest(x \to x^2, 2, 3)", "256"),
+        (r"\nest(x \to 1 + 1/x, 1, 4)", "8/5"),
+        (r"\map(2, (1, 2))", "arguments error"),
+        (r"\nest(x \to x, 1, -1)", "arguments error"),
+        (r"\nest(x \to x, 1, 10000)", "1"),
+        (r"\nest(x \to x, 1, 1000000000)", "too many iterations"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
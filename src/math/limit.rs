@@ -0,0 +1,308 @@
+use super::builtin::{Cmd, Function};
+use super::diff::partial;
+use super::error::{ErrorKind, EvalError};
+use super::expr::{Expr, Sign, ONE, ZERO};
+use super::func;
+use super::num::Num;
+use super::series::{self, Series};
+use crate::interpreter::environment::Environment;
+
+#[cfg(test)]
+mod test;
+
+pub const LIMIT: Function = Function {
+    name: "\\limit",
+    arity: 3..=4,
+    help: "\\limit(f, x, x0, dir): limit of f as x tends to x0, from the right if dir = 1 and from the left if dir = -1",
+    eval: lib_limit,
+};
+pub const CMD_LIMIT: Expr = Expr::Cmd(Cmd(&LIMIT));
+
+/// Maximum number of nested applications of L'Hôpital's rule.
+const MAX_DEPTH: u32 = 8;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Dir {
+    Both,
+    Right,
+    Left,
+}
+
+/// Return the limit of `e` as `var` approaches `point` from `dir`.
+pub fn limit(
+    e: Expr,
+    var: &str,
+    point: Expr,
+    dir: Dir,
+) -> Result<Expr, EvalError> {
+    // Limits at infinity are limits at zero from the right after
+    // substituting `var = 1 / var` or `var = -1 / var`.
+    let (e, point, dir) = match point {
+        Expr::Inf(s) => {
+            let t = Expr::Sym(var.to_string());
+            let t = match s {
+                Sign::Pos => Expr::pow(t, Expr::int(-1)),
+                Sign::Neg => Expr::int(-1) * Expr::pow(t, Expr::int(-1)),
+            };
+            (e.subs(var, &t), ZERO, Dir::Right)
+        }
+        point => (e, point, dir),
+    };
+    lim(&e, var, &point, dir, 0)
+}
+
+fn lim(
+    e: &Expr,
+    var: &str,
+    point: &Expr,
+    dir: Dir,
+    depth: u32,
+) -> Result<Expr, EvalError> {
+    if !e.has_sym(var) {
+        return Ok(e.clone());
+    }
+    if let Ok(s) = series::expand_to(e, var, point, 1) {
+        return from_series(&s, dir);
+    }
+    let is_zero = |l: &Expr| *l == ZERO;
+    let is_inf = |l: &Expr| matches!(l, Expr::Inf(_));
+    match e {
+        Expr::Add(a) => {
+            let ls = a
+                .clone()
+                .into_args()
+                .iter()
+                .map(|e| lim(e, var, point, dir, depth))
+                .collect();
+            let mut l = ZERO;
+            for e in known(ls, |l| !is_inf(l))? {
+                l = l.try_add(e)?;
+            }
+            Ok(l)
+        }
+        Expr::Mul(m) => {
+            let args = m.clone().into_args();
+            let ls: Vec<_> = args
+                .iter()
+                .map(|e| lim(e, var, point, dir, depth))
+                .collect();
+            // A bounded factor times factors tending to zero tends to zero.
+            let squeezed = ls.iter().any(|l| matches!(l, Ok(l) if is_zero(l)))
+                && args.iter().zip(&ls).all(|(e, l)| match l {
+                    Ok(l) => !is_inf(l),
+                    Err(_) => is_bounded(e),
+                });
+            if squeezed {
+                return Ok(ZERO);
+            }
+            let ls = known(ls, |l| !is_zero(l) && !is_inf(l))?;
+            if ls.iter().any(is_zero) && ls.iter().any(is_inf) {
+                // Rewrite `0 * \infty` as `\infty / \infty`.
+                let mut num = ONE;
+                let mut den = ONE;
+                for (e, l) in args.into_iter().zip(ls.iter()) {
+                    if is_zero(l) {
+                        den *= Expr::pow(e, Expr::int(-1));
+                    } else {
+                        num *= e;
+                    }
+                }
+                return lhopital(num, den, var, point, dir, depth);
+            }
+            let mut l = ONE;
+            for e in ls {
+                l = l.try_mul(e)?;
+            }
+            Ok(l)
+        }
+        Expr::Pow(base, exp) if !exp.has_sym(var) => {
+            match (sub_limit(base, var, point, dir, depth)?, &**exp) {
+                (ZERO, Expr::Num(n)) if n.signum() < 0 => {
+                    Err(EvalError::unsupported("cannot determine limit"))
+                }
+                (l, exp) => l.try_pow(exp.clone()),
+            }
+        }
+        Expr::Pow(base, exp) if matches!(**base, Expr::Num(_)) => base
+            .clone()
+            .try_pow(sub_limit(exp, var, point, dir, depth)?),
+        Expr::Pow(base, exp) => {
+            let e = func::exp(*exp.clone() * func::ln(*base.clone()));
+            lim(&e, var, point, dir, depth)
+        }
+        Expr::Call(f, x) => match **f {
+            Expr::Cmd(c) => {
+                let l = sub_limit(x, var, point, dir, depth)?;
+                // The logarithm tends to `-\infty` only from the right.
+                if c.name() == "\\ln"
+                    && l == ZERO
+                    && sign_near(x, var, point, dir)? != Some(Sign::Pos)
+                {
+                    return Err(EvalError::domain(
+                        "logarithm of a negative number",
+                    ));
+                }
+                apply(f, c.name(), l)
+            }
+            _ => Err(EvalError::unsupported("cannot determine limit")),
+        },
+        _ => Err(EvalError::unsupported("cannot determine limit")),
+    }
+}
+
+/// Return the limit of the operand of a function or power. If the operand
+/// has no limit, whether the whole expression has one is not known.
+fn sub_limit(
+    e: &Expr,
+    var: &str,
+    point: &Expr,
+    dir: Dir,
+    depth: u32,
+) -> Result<Expr, EvalError> {
+    lim(e, var, point, dir, depth).map_err(|err| match err.kind {
+        ErrorKind::Undefined => {
+            EvalError::unsupported("cannot determine limit")
+        }
+        _ => err,
+    })
+}
+
+/// Return the limits of the terms of a sum or the factors of a product.
+/// A single term without a limit makes the whole have none if the others
+/// have limits for which `finite` holds. Otherwise whether the whole has a
+/// limit is not known.
+fn known<F>(
+    ls: Vec<Result<Expr, EvalError>>,
+    finite: F,
+) -> Result<Vec<Expr>, EvalError>
+where
+    F: Fn(&Expr) -> bool,
+{
+    let failed = ls.iter().filter(|l| l.is_err()).count();
+    let others = ls.iter().all(|l| l.as_ref().map_or(true, &finite));
+    let mut v = Vec::new();
+    for l in ls {
+        match l {
+            Ok(l) => v.push(l),
+            Err(e) if e.kind != ErrorKind::Undefined => return Err(e),
+            Err(e) if failed == 1 && others => return Err(e),
+            Err(_) => {
+                return Err(EvalError::unsupported("cannot determine limit"))
+            }
+        }
+    }
+    Ok(v)
+}
+
+/// Return whether `e` is bounded whatever its argument.
+fn is_bounded(e: &Expr) -> bool {
+    match e {
+        Expr::Call(f, _) => f.is_cmd("\\sin") || f.is_cmd("\\cos"),
+        _ => false,
+    }
+}
+
+/// Apply L'Hôpital's rule to `num / den` of the form `0 / 0` or
+/// `\infty / \infty`.
+fn lhopital(
+    num: Expr,
+    den: Expr,
+    var: &str,
+    point: &Expr,
+    dir: Dir,
+    depth: u32,
+) -> Result<Expr, EvalError> {
+    if depth >= MAX_DEPTH {
+        return Err(EvalError::unsupported("cannot determine limit"));
+    }
+    let e = partial(num, var) / partial(den, var);
+    lim(&e, var, point, dir, depth + 1)
+}
+
+/// Return the sign of `e`, which tends to zero, as `var` approaches
+/// `point` from `dir`, or `None` if it differs on the two sides.
+fn sign_near(
+    e: &Expr,
+    var: &str,
+    point: &Expr,
+    dir: Dir,
+) -> Result<Option<Sign>, EvalError> {
+    let unknown = || EvalError::unsupported("cannot determine sign");
+    let leading = [2, 4, 8, 16].iter().find_map(|&order| {
+        let s = series::expand_to(e, var, point, order).ok()?;
+        s.leading_term().map(|(k, c)| (k, c.clone()))
+    });
+    let (k, c) = leading.ok_or_else(unknown)?;
+    let sign = match c {
+        Expr::Num(ref n) if n.signum() > 0 => Sign::Pos,
+        Expr::Num(_) => Sign::Neg,
+        _ => return Err(unknown()),
+    };
+    match dir {
+        _ if k % 2 == 0 => Ok(Some(sign)),
+        Dir::Right => Ok(Some(sign)),
+        Dir::Left => Ok(Some(sign * Sign::Neg)),
+        Dir::Both => Ok(None),
+    }
+}
+
+fn from_series(s: &Series, dir: Dir) -> Result<Expr, EvalError> {
+    match s.leading_term() {
+        Some((k, c)) if k < 0 => {
+            let sign = match *c {
+                Expr::Num(ref n) if n.signum() > 0 => Sign::Pos,
+                Expr::Num(_) => Sign::Neg,
+                _ => {
+                    return Err(EvalError::unsupported("cannot determine sign"))
+                }
+            };
+            let side = match dir {
+                _ if k % 2 == 0 => Sign::Pos,
+                Dir::Right => Sign::Pos,
+                Dir::Left => Sign::Neg,
+                Dir::Both => {
+                    return Err(EvalError::undefined(
+                        "left and right limits differ",
+                    ))
+                }
+            };
+            Ok(Expr::Inf(sign * side))
+        }
+        Some((0, c)) => Ok(c.clone()),
+        _ if s.order() > 0 => Ok(ZERO),
+        _ => Err(EvalError::unsupported("cannot determine limit")),
+    }
+}
+
+/// Return `f(l)` for the limit `l` of the argument of an elementary
+/// function `f`.
+fn apply(f: &Expr, name: &str, l: Expr) -> Result<Expr, EvalError> {
+    match (name, l) {
+        ("\\exp", Expr::Inf(Sign::Pos)) => Ok(Expr::Inf(Sign::Pos)),
+        ("\\exp", Expr::Inf(Sign::Neg)) => Ok(ZERO),
+        ("\\ln", Expr::Inf(Sign::Pos)) => Ok(Expr::Inf(Sign::Pos)),
+        ("\\ln", ZERO) => Ok(Expr::Inf(Sign::Neg)),
+        ("\\sin", Expr::Inf(_)) | ("\\cos", Expr::Inf(_)) => {
+            Err(EvalError::undefined("limit does not exist"))
+        }
+        (_, Expr::Inf(_)) => {
+            Err(EvalError::unsupported("cannot determine limit"))
+        }
+        (_, l) => Expr::call_in(f.clone(), l, &Environment::new()),
+    }
+}
+
+pub fn lib_limit(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    let dir = match args.get(3) {
+        None => Some(Dir::Both),
+        Some(&Expr::Num(Num::Int(1))) => Some(Dir::Right),
+        Some(&Expr::Num(Num::Int(-1))) => Some(Dir::Left),
+        Some(_) => None,
+    };
+    if let (Some(dir), [e, Expr::Sym(x), x0, ..]) = (dir, args.as_slice()) {
+        if !x0.has_sym(x) {
+            return limit(e.clone(), x, x0.clone(), dir);
+        }
+    }
+    Err(EvalError::type_mismatch("arguments error"))
+}
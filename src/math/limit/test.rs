@@ -0,0 +1,67 @@
+use super::super::utl_test::*;
+
+#[test]
+fn test_limit() {
+    let tests = [
+        (r"\limit(x^2 + 1, x, 2)", "5"),
+        (r"\limit(a x, x, 2)", "(2 * a)"),
+        (r"\limit((x^2 - 1)/(x - 1), x, 1)", "2"),
+        (r"\limit(\sin(x)/x, x, 0)", "1"),
+        (r"\limit((1 - \cos(x))/x^2, x, 0)", "1/2"),
+        (r"\limit(1/x^2, x, 0)", r"\infty"),
+        (r"\limit(-1/x^2, x, 0)", r"-\infty"),
+        (r"\limit(1/x, x, 0)", "left and right limits differ"),
+        (r"\limit(a/x^2, x, 0)", "cannot determine sign"),
+        (r"\limit(x \sin(1/x), x, 0)", "0"),
+        (r"\limit(x^2 \cos(1/x) + 1, x, 0)", "1"),
+        (r"\limit(\sin(1/x), x, 0)", "cannot determine limit"),
+        (r"\limit(1/x + 1, x, 0)", "left and right limits differ"),
+        (r"\limit(x, 1, 0)", "arguments error"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_one_sided_limit() {
+    let tests = [
+        (r"\limit(1/x, x, 0, 1)", r"\infty"),
+        (r"\limit(1/x, x, 0, -1)", r"-\infty"),
+        (r"\limit(\ln(x), x, 0, 1)", r"-\infty"),
+        (r"\limit(x \ln(x), x, 0, 1)", "0"),
+        (r"\limit(x^x, x, 0, 1)", "1"),
+        (
+            r"\limit(\ln(x), x, 0, -1)",
+            "logarithm of a negative number",
+        ),
+        (r"\limit(\ln(-x), x, 0, -1)", r"-\infty"),
+        (r"\limit(\ln(x^2), x, 0)", r"-\infty"),
+        (r"\limit(1/x, x, 0, 2)", "arguments error"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_limit_at_infinity() {
+    let tests = [
+        (r"\limit((2x^2 + 1)/(x^2 + 3), x, \infty)", "2"),
+        (r"\limit(x^3 - x, x, -\infty)", r"-\infty"),
+        (r"\limit(\exp(x), x, \infty)", r"\infty"),
+        (r"\limit(\exp(x), x, -\infty)", "0"),
+        (r"\limit(\ln(x)/x, x, \infty)", "0"),
+        (r"\limit(\exp(-x) x, x, \infty)", "0"),
+        (r"\limit((1 + 1/x)^x, x, \infty)", r"\exp(1)"),
+        (r"\limit(\sin(x), x, \infty)", "limit does not exist"),
+        (r"\limit(2^x, x, \infty)", r"\infty"),
+        (r"\limit(x (1/2)^x, x, \infty)", "0"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
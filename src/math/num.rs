@@ -1,9 +1,11 @@
+use std::cell::Cell;
+use std::convert::TryFrom;
 use std::{fmt, mem};
 
 #[cfg(test)]
 mod test;
 
-fn gcd(n: i32, m: i32) -> i32 {
+pub fn gcd(n: i32, m: i32) -> i32 {
     if m == 0 {
         n
     } else {
@@ -11,6 +13,41 @@ fn gcd(n: i32, m: i32) -> i32 {
     }
 }
 
+thread_local! {
+    static OVERFLOW: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Record that an operator overflowed and return zero as its result.
+/// Operators such as `+` cannot return an error, so the evaluator checks
+/// the record with `take_overflow` instead.
+fn overflow() -> Num {
+    OVERFLOW.with(|f| f.set(true));
+    ZERO
+}
+
+/// Return whether an operator on numbers overflowed since the last call
+/// and clear the record.
+pub fn take_overflow() -> bool {
+    OVERFLOW.with(|f| f.replace(false))
+}
+
+/// Return `num / den` in lowest terms, or `None` if it does not fit.
+fn reduce(num: i64, den: i64) -> Option<Num> {
+    let (mut a, mut b) = (num.unsigned_abs(), den.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    let g = a.max(1) as i64;
+    let (num, den) = if den < 0 { (-num, -den) } else { (num, den) };
+    let num = i32::try_from(num / g).ok()?;
+    let den = i32::try_from(den / g).ok()?;
+    if den == 1 {
+        Some(Num::Int(num))
+    } else {
+        Some(Num::Rat(num, den))
+    }
+}
+
 pub const ZERO: Num = Num::Int(0);
 pub const ONE: Num = Num::Int(1);
 pub const NEG_ONE: Num = Num::Int(-1);
@@ -27,14 +64,13 @@ impl Num {
     }
 
     pub fn rational(num: i32, den: i32) -> Num {
-        let p = num * den;
-        let g = gcd(num, den);
-        let num = (num / g).abs() * if p >= 0 { 1 } else { -1 };
-        let den = (den / g).abs();
-        if den == 1 {
-            Num::int(num)
-        } else {
-            Num::Rat(num, den)
+        reduce(num.into(), den.into()).unwrap_or_else(overflow)
+    }
+
+    pub fn signum(&self) -> i32 {
+        match self {
+            Num::Int(i) => i.signum(),
+            Num::Rat(n, _) => n.signum(),
         }
     }
 
@@ -45,20 +81,64 @@ impl Num {
     //     }
     // }
 
-    pub fn pow(self, exp: i32) -> Num {
-        match (self, exp) {
-            (Num::Int(i), exp @ 0..) => Num::int(i.pow(exp.unsigned_abs())),
-            (Num::Int(i), exp) => Num::rational(1, i.pow(exp.unsigned_abs())),
-            (Num::Rat(n, d), exp @ 0..) => {
-                let exp = exp.unsigned_abs();
-                Num::Rat(n.pow(exp), d.pow(exp))
-            }
-            (Num::Rat(n, d), exp) => {
-                let exp = exp.unsigned_abs();
-                Num::Rat(d.pow(exp), n.pow(exp))
+    /// Return the square root if it is rational.
+    pub fn sqrt(&self) -> Option<Num> {
+        let isqrt = |i: i32| {
+            let r = (i as f64).sqrt().round() as i32;
+            if i >= 0 && r.checked_mul(r) == Some(i) {
+                Some(r)
+            } else {
+                None
             }
+        };
+        match *self {
+            Num::Int(i) => isqrt(i).map(Num::int),
+            Num::Rat(n, d) => Some(Num::rational(isqrt(n)?, isqrt(d)?)),
         }
     }
+
+    /// Return `self + rhs`, or `None` on overflow.
+    pub fn checked_add(&self, rhs: &Num) -> Option<Num> {
+        let (n1, d1) = self.wide_parts();
+        let (n2, d2) = rhs.wide_parts();
+        reduce(n1 * d2 + n2 * d1, d1 * d2)
+    }
+
+    /// Return `self * rhs`, or `None` on overflow.
+    pub fn checked_mul(&self, rhs: &Num) -> Option<Num> {
+        let (n1, d1) = self.wide_parts();
+        let (n2, d2) = rhs.wide_parts();
+        reduce(n1 * n2, d1 * d2)
+    }
+
+    /// Return `self ^ exp`, or `None` on overflow.
+    pub fn checked_pow(&self, exp: i32) -> Option<Num> {
+        let (n, d) = self.wide_parts();
+        let e = exp.unsigned_abs();
+        let (n, d) = (n.checked_pow(e)?, d.checked_pow(e)?);
+        if exp >= 0 {
+            reduce(n, d)
+        } else {
+            reduce(d, n)
+        }
+    }
+
+    /// Return the numerator and the denominator.
+    pub fn parts(&self) -> (i32, i32) {
+        match *self {
+            Num::Int(i) => (i, 1),
+            Num::Rat(n, d) => (n, d),
+        }
+    }
+
+    fn wide_parts(&self) -> (i64, i64) {
+        let (n, d) = self.parts();
+        (n.into(), d.into())
+    }
+
+    pub fn pow(self, exp: i32) -> Num {
+        self.checked_pow(exp).unwrap_or_else(overflow)
+    }
 }
 
 impl Default for Num {
@@ -79,15 +159,7 @@ impl fmt::Debug for Num {
 impl std::ops::Add for Num {
     type Output = Num;
     fn add(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Num::Int(i), Num::Int(j)) => Num::int(i + j),
-            (Num::Int(i), Num::Rat(n, d)) | (Num::Rat(n, d), Num::Int(i)) => {
-                Num::rational(n + d * i, d)
-            }
-            (Num::Rat(n1, d1), Num::Rat(n2, d2)) => {
-                Num::rational(n1 * d2 + n2 * d1, d1 * d2)
-            }
-        }
+        self.checked_add(&rhs).unwrap_or_else(overflow)
     }
 }
 
@@ -124,15 +196,7 @@ impl std::ops::AddAssign for Num {
 impl std::ops::Mul for Num {
     type Output = Num;
     fn mul(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (Num::Int(i), Num::Int(j)) => Num::int(i * j),
-            (Num::Int(i), Num::Rat(n, d)) | (Num::Rat(n, d), Num::Int(i)) => {
-                Num::rational(n * i, d)
-            }
-            (Num::Rat(n1, d1), Num::Rat(n2, d2)) => {
-                Num::rational(n1 * n2, d1 * d2)
-            }
-        }
+        self.checked_mul(&rhs).unwrap_or_else(overflow)
     }
 }
 
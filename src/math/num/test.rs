@@ -21,3 +21,23 @@ fn test_ops() {
         Num::rational(1, 4)
     );
 }
+
+#[test]
+fn test_overflow() {
+    take_overflow();
+    assert_eq!(
+        Num::rational(1, 65536) + Num::rational(1, 65536),
+        Num::rational(1, 32768)
+    );
+    assert!(!take_overflow());
+    assert_eq!(Num::int(46341).sqrt(), None);
+    assert_eq!(Num::int(65536) * Num::int(32768), ZERO);
+    assert!(take_overflow());
+    assert!(!take_overflow());
+    assert_eq!(Num::int(2).pow(31), ZERO);
+    assert!(take_overflow());
+    assert_eq!(
+        Num::int(2).checked_pow(-30),
+        Some(Num::rational(1, 1 << 30))
+    );
+}
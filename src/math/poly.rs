@@ -0,0 +1,197 @@
+use super::expr::{Expr, NEG_ONE, ONE, ZERO};
+use super::num::{self, Num};
+
+/// Return the coefficients of `e` as a polynomial in `var`, lowest degree
+/// first, or `None` if `e` is not a polynomial in `var`.
+pub fn coeffs(e: &Expr, var: &str) -> Option<Vec<Expr>> {
+    if !e.has_sym(var) {
+        return Some(vec![e.clone()]);
+    }
+    match e {
+        Expr::Sym(_) => Some(vec![ZERO, ONE]),
+        Expr::Add(a) => {
+            let mut p = Vec::new();
+            for e in a.clone().into_args() {
+                p = add(&p, &coeffs(&e, var)?);
+            }
+            Some(p)
+        }
+        Expr::Mul(m) => {
+            let mut p = vec![ONE];
+            for e in m.clone().into_args() {
+                p = mul(&p, &coeffs(&e, var)?);
+            }
+            Some(p)
+        }
+        Expr::Pow(base, exp) => match **exp {
+            Expr::Num(Num::Int(n)) if n >= 0 => {
+                let base = coeffs(base, var)?;
+                let mut p = vec![ONE];
+                for _ in 0..n {
+                    p = mul(&p, &base);
+                }
+                Some(p)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub fn add(p: &[Expr], q: &[Expr]) -> Vec<Expr> {
+    let mut r = vec![ZERO; p.len().max(q.len())];
+    for (i, c) in p.iter().enumerate() {
+        r[i] += c.clone();
+    }
+    for (i, c) in q.iter().enumerate() {
+        r[i] += c.clone();
+    }
+    r
+}
+
+pub fn mul(p: &[Expr], q: &[Expr]) -> Vec<Expr> {
+    let mut r = vec![ZERO; (p.len() + q.len()).saturating_sub(1)];
+    for (i, a) in p.iter().enumerate() {
+        for (j, b) in q.iter().enumerate() {
+            r[i + j] += a.clone() * b.clone();
+        }
+    }
+    r
+}
+
+pub fn eval(p: &[Expr], x: &Expr) -> Expr {
+    Expr::sum(
+        p.iter().enumerate().map(|(i, c)| {
+            c.clone() * Expr::pow(x.clone(), Expr::int(i as i32))
+        }),
+    )
+}
+
+fn divisors(n: i32) -> Vec<i32> {
+    let n = i64::from(n).abs();
+    let mut d = Vec::new();
+    let mut i = 1;
+    while i * i <= n {
+        if n % i == 0 {
+            d.push(i as i32);
+            d.push((n / i) as i32);
+        }
+        i += 1;
+    }
+    d
+}
+
+/// Split off the rational roots of the polynomial `p` with rational
+/// coefficients, returning them with multiplicity and the quotient.
+pub fn rational_roots(p: Vec<Num>) -> (Vec<Num>, Vec<Num>) {
+    let mut p = p;
+    while p.last() == Some(&num::ZERO) {
+        p.pop();
+    }
+    let mut roots = Vec::new();
+    while p.len() > 1 && p[0] == num::ZERO {
+        p.remove(0);
+        roots.push(num::ZERO);
+    }
+    'deflate: while p.len() > 1 {
+        // Scale to integer coefficients for the rational root test, which
+        // is skipped if they do not fit.
+        let den = p.iter().try_fold(1i32, |d, c| match *c {
+            Num::Rat(_, n) => (d / num::gcd(d, n)).checked_mul(n),
+            Num::Int(_) => Some(d),
+        });
+        let ints: Option<Vec<i32>> = den.and_then(|den| {
+            p.iter()
+                .map(|c| match c.checked_mul(&Num::int(den))? {
+                    Num::Int(i) => Some(i),
+                    Num::Rat(..) => unreachable!(),
+                })
+                .collect()
+        });
+        let ints = match ints {
+            Some(ints) => ints,
+            None => break,
+        };
+        for q in divisors(ints[ints.len() - 1]) {
+            for s in divisors(ints[0]) {
+                for s in [s, -s] {
+                    let r = Num::rational(s, q);
+                    let mut quot = Vec::new();
+                    let mut acc = Some(num::ZERO);
+                    for c in p.iter().rev() {
+                        acc =
+                            acc.and_then(|a| a.checked_mul(&r)?.checked_add(c));
+                        quot.extend(acc.clone());
+                    }
+                    // A value which does not fit is not zero.
+                    if acc == Some(num::ZERO) {
+                        quot.pop();
+                        quot.reverse();
+                        p = quot;
+                        roots.push(r);
+                        continue 'deflate;
+                    }
+                }
+            }
+        }
+        break;
+    }
+    (roots, p)
+}
+
+/// Return the polynomial with rational coefficients `p` in `x` as a
+/// product of its linear factors over the rationals and the rest, or
+/// `None` if a coefficient does not fit.
+pub fn factor(p: Vec<Num>, x: &Expr) -> Option<Expr> {
+    let (roots, rest) = rational_roots(p);
+    let mut factors = Vec::new();
+    let mut c = num::ONE;
+    for r in roots {
+        // x - s / q = (q x - s) / q
+        let (s, q) = match r {
+            Num::Int(i) => (i, 1),
+            Num::Rat(s, q) => (s, q),
+        };
+        factors.push(Expr::int(q) * x.clone() - Expr::int(s));
+        c = c.checked_mul(&Num::rational(1, q))?;
+    }
+    let rest: Option<Vec<Expr>> = rest
+        .into_iter()
+        .map(|n| n.checked_mul(&c).map(Expr::Num))
+        .collect();
+    factors.push(eval(&rest?, x));
+    Some(Expr::prod(factors))
+}
+
+/// Solve the linear system `m b = rhs` by Gauss-Jordan elimination.
+pub fn solve_linear(m: Vec<Vec<Expr>>, rhs: Vec<Expr>) -> Option<Vec<Expr>> {
+    let (mut m, mut b) = (m, rhs);
+    let n = m.first().map_or(0, Vec::len);
+    for col in 0..n {
+        let p = (col..m.len()).find(|&i| m[i][col] != ZERO)?;
+        m.swap(col, p);
+        b.swap(col, p);
+        let inv = Expr::pow(m[col][col].clone(), NEG_ONE);
+        for e in m[col].iter_mut() {
+            *e = (std::mem::take(e) * inv.clone()).expand();
+        }
+        b[col] = (std::mem::take(&mut b[col]) * inv).expand();
+        let pivot = m[col].clone();
+        for i in 0..m.len() {
+            let f = m[i][col].clone();
+            if i == col || f == ZERO {
+                continue;
+            }
+            for (e, p) in m[i].iter_mut().zip(&pivot) {
+                *e = (std::mem::take(e) - f.clone() * p.clone()).expand();
+            }
+            let e = f * b[col].clone();
+            b[i] = (std::mem::take(&mut b[i]) - e).expand();
+        }
+    }
+    if b[n..].iter().any(|e| *e != ZERO) {
+        return None;
+    }
+    b.truncate(n);
+    Some(b)
+}
@@ -0,0 +1,421 @@
+use std::fmt;
+
+use super::builtin::{Cmd, Function};
+use super::diff::diff;
+use super::error::EvalError;
+use super::expr::{Expr, ONE, ZERO};
+use super::func;
+use super::num::{self, Num};
+
+#[cfg(test)]
+mod test;
+
+pub const SERIES: Function = Function {
+    name: "\\series",
+    arity: 4..=4,
+    help: "\\series(f, x, x0, n): expansion of f around x = x0 up to order n",
+    eval: lib_series,
+};
+pub const CMD_SERIES: Expr = Expr::Cmd(Cmd(&SERIES));
+
+/// Symbol used to differentiate an elementary function independently of
+/// the user's variables. It cannot be produced by the lexer.
+const DUMMY: &str = "\\_";
+
+const DIV_ZERO: &str = "division by zero";
+
+/// Maximum order accepted by `\series`. Higher orders need coefficients
+/// such as `1/13!` in the expansion of `\exp`, which overflow the integer
+/// type.
+const MAX_ORDER: i32 = 13;
+
+/// Truncated series `c_0 (x - x0)^start + c_1 (x - x0)^(start + 1) + ...`
+/// together with its order term `O((x - x0)^order)`.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Series {
+    var: String,
+    point: Expr,
+    start: i32,
+    coeffs: Vec<Expr>,
+    order: i32,
+}
+
+impl fmt::Debug for Series {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        for term in self.terms() {
+            write!(f, "{:?} + ", term)?;
+        }
+        write!(f, "O({:?}))", self.order_term())
+    }
+}
+
+impl Series {
+    fn new(
+        var: &str,
+        point: Expr,
+        start: i32,
+        coeffs: Vec<Expr>,
+        order: i32,
+    ) -> Series {
+        let start = start.min(order);
+        let mut coeffs = coeffs;
+        coeffs.resize((order - start) as usize, ZERO);
+        Series {
+            var: var.to_string(),
+            point,
+            start,
+            coeffs,
+            order,
+        }
+    }
+
+    fn constant(e: Expr, var: &str, point: &Expr, order: i32) -> Series {
+        Series::new(var, point.clone(), 0, vec![e], order)
+    }
+
+    pub fn var(&self) -> &str {
+        &self.var
+    }
+
+    pub fn point(&self) -> &Expr {
+        &self.point
+    }
+
+    /// Return the nonzero terms `c_k (x - x0)^k` in increasing order of `k`.
+    pub fn terms(&self) -> Vec<Expr> {
+        let x = self.shifted_var();
+        (self.start..)
+            .zip(&self.coeffs)
+            .filter(|&(_, c)| *c != ZERO)
+            .map(|(k, c)| c.clone() * Expr::pow(x.clone(), Expr::int(k)))
+            .collect()
+    }
+
+    /// Return `(x - x0)^order`, the argument of the order term.
+    pub fn order_term(&self) -> Expr {
+        Expr::pow(self.shifted_var(), Expr::int(self.order))
+    }
+
+    /// Return `x - x0`.
+    fn shifted_var(&self) -> Expr {
+        Expr::Sym(self.var.clone()) - self.point.clone()
+    }
+
+    /// Return the coefficient of `(x - x0)^k`.
+    fn coeff(&self, k: i32) -> Expr {
+        if k < self.start || k >= self.order {
+            ZERO
+        } else {
+            self.coeffs[(k - self.start) as usize].clone()
+        }
+    }
+
+    pub fn order(&self) -> i32 {
+        self.order
+    }
+
+    /// Return the lowest power with a nonzero coefficient and the
+    /// coefficient, or `None` if every known coefficient vanishes.
+    pub fn leading_term(&self) -> Option<(i32, &Expr)> {
+        (self.start..)
+            .zip(self.coeffs.iter())
+            .find(|(_, c)| **c != ZERO)
+    }
+
+    pub fn has_sym(&self, sym: &str) -> bool {
+        self.var == sym
+            || self.point.has_sym(sym)
+            || self.coeffs.iter().any(|c| c.has_sym(sym))
+    }
+
+    fn check(&self, other: &Series) -> Result<(), EvalError> {
+        if self.var == other.var && self.point == other.point {
+            Ok(())
+        } else {
+            Err(EvalError::unsupported("series around different points"))
+        }
+    }
+
+    fn truncate(self, order: i32) -> Series {
+        if order < self.order {
+            Series::new(&self.var, self.point, self.start, self.coeffs, order)
+        } else {
+            self
+        }
+    }
+
+    /// Drop leading zero coefficients so that `start` is the valuation.
+    fn strip(mut self) -> Series {
+        let n = self.coeffs.iter().take_while(|c| **c == ZERO).count();
+        self.coeffs.drain(..n);
+        self.start += n as i32;
+        self
+    }
+
+    fn add(self, other: Series) -> Result<Series, EvalError> {
+        self.check(&other)?;
+        let start = self.start.min(other.start);
+        let order = self.order.min(other.order);
+        let coeffs = (start..order)
+            .map(|k| self.coeff(k) + other.coeff(k))
+            .collect();
+        Ok(Series::new(&self.var, self.point, start, coeffs, order))
+    }
+
+    fn mul(self, other: Series) -> Result<Series, EvalError> {
+        self.check(&other)?;
+        let start = self.start + other.start;
+        let order = (self.start + other.order).min(other.start + self.order);
+        let len = (order - start).max(0) as usize;
+        let mut coeffs = vec![ZERO; len];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in other.coeffs.iter().enumerate() {
+                if i + j < len && *a != ZERO && *b != ZERO {
+                    coeffs[i + j] += a.clone() * b.clone();
+                }
+            }
+        }
+        Ok(Series::new(&self.var, self.point, start, coeffs, order))
+    }
+
+    fn powi(self, exp: i32) -> Result<Series, EvalError> {
+        if exp < 0 {
+            return self.powr(&Num::int(exp));
+        }
+        let mut s = Series::constant(ONE, &self.var, &self.point, self.order);
+        for _ in 0..exp {
+            s = s.mul(self.clone())?;
+        }
+        Ok(s)
+    }
+
+    /// Raise the series to a rational power with the binomial series of
+    /// `(1 + w)^exp` after factoring out the leading term.
+    fn powr(self, exp: &Num) -> Result<Series, EvalError> {
+        let s = self.strip();
+        let a0 = match s.coeffs.first() {
+            Some(a0) => a0.clone(),
+            None => return Err(EvalError::domain(DIV_ZERO)),
+        };
+        let start = match Num::int(s.start) * exp.clone() {
+            Num::Int(i) => i,
+            Num::Rat(..) => return Err(EvalError::unsupported("branch point")),
+        };
+        let n = s.order - s.start;
+        let mut w = s.coeffs.clone();
+        w[0] = ZERO;
+        for c in w.iter_mut().skip(1) {
+            *c = std::mem::take(c) / a0.clone();
+        }
+        let w = Series::new(&s.var, s.point.clone(), 0, w, n);
+
+        let mut binom = vec![num::ONE];
+        for k in 1..n {
+            let b = binom[k as usize - 1].clone()
+                * (exp.clone() + Num::int(1 - k))
+                * Num::rational(1, k);
+            binom.push(b);
+        }
+        let mut r = Series::constant(ZERO, &s.var, &s.point, n);
+        for b in binom.into_iter().rev() {
+            r = r.mul(w.clone())?;
+            r = r.add(Series::constant(Expr::Num(b), &s.var, &s.point, n))?;
+        }
+
+        let a0 = Expr::pow(a0, Expr::Num(exp.clone()));
+        let coeffs = r.coeffs.into_iter().map(|c| c * a0.clone()).collect();
+        Ok(Series::new(&s.var, s.point, start, coeffs, start + r.order))
+    }
+}
+
+/// Expand `f(u)` for an elementary function `f` with the Taylor series of
+/// `f` around the constant term of `u`, whose coefficients are computed
+/// by repeated differentiation.
+fn compose(f: &Expr, u: Series) -> Result<Series, EvalError> {
+    let u = u.strip();
+    if u.start < 0 {
+        return Err(EvalError::unsupported("essential singularity"));
+    }
+    let u0 = u.coeff(0);
+    if f.is_cmd("\\ln") && u0 == ZERO {
+        return Err(EvalError::unsupported("logarithmic singularity"));
+    }
+    let mut v = u.clone();
+    if v.start == 0 && !v.coeffs.is_empty() {
+        v.coeffs[0] = ZERO;
+    }
+
+    let mut d = Vec::new();
+    let mut g = Expr::call(f.clone(), Expr::Sym(DUMMY.to_string()));
+    let mut fact = num::ONE;
+    for k in 0..u.order.max(1) {
+        if k > 0 {
+            g = diff(g, DUMMY);
+            fact = fact
+                .checked_mul(&Num::int(k))
+                .ok_or_else(|| EvalError::overflow("integer overflow"))?;
+        }
+        d.push(g.clone().subs(DUMMY, &u0) / Expr::Num(fact.clone()));
+    }
+
+    let mut r = Series::constant(ZERO, &u.var, &u.point, u.order);
+    for c in d.into_iter().rev() {
+        r = r.mul(v.clone())?;
+        r = r.add(Series::constant(c, &u.var, &u.point, u.order))?;
+    }
+    Ok(r)
+}
+
+fn expand(
+    e: &Expr,
+    var: &str,
+    point: &Expr,
+    order: i32,
+) -> Result<Series, EvalError> {
+    if !e.has_sym(var) {
+        return Ok(Series::constant(e.clone(), var, point, order));
+    }
+    match e {
+        // `x = x0 + (x - x0)` is exact, so keep its linear term even when
+        // few terms are requested to allow division by it.
+        Expr::Sym(_) => Ok(Series::new(
+            var,
+            point.clone(),
+            0,
+            vec![point.clone(), ONE],
+            order.max(2),
+        )),
+        Expr::Add(a) => {
+            let mut s = Series::constant(ZERO, var, point, order);
+            for e in a.clone().into_args() {
+                s = s.add(expand(&e, var, point, order)?)?;
+            }
+            Ok(s)
+        }
+        Expr::Mul(m) => {
+            let mut s = Series::constant(ONE, var, point, order);
+            for e in m.clone().into_args() {
+                s = s.mul(expand(&e, var, point, order)?)?;
+            }
+            Ok(s)
+        }
+        Expr::Pow(base, exp) => match **exp {
+            Expr::Num(Num::Int(i)) => expand(base, var, point, order)?.powi(i),
+            Expr::Num(ref n) => expand(base, var, point, order)?.powr(n),
+            ref exp => {
+                let e = func::exp(exp.clone() * func::ln(*base.clone()));
+                expand(&e, var, point, order)
+            }
+        },
+        Expr::Call(f, x) => match **f {
+            Expr::Cmd(c) if func::is_elementary(c.name()) => {
+                compose(f, expand(x, var, point, order)?)
+            }
+            _ => Err(EvalError::unsupported("cannot expand")),
+        },
+        Expr::Series(s) => {
+            let s = (**s).clone();
+            s.check(&Series::constant(ZERO, var, point, order))?;
+            Ok(s.truncate(order))
+        }
+        _ => Err(EvalError::unsupported("cannot expand")),
+    }
+}
+
+fn to_series(e: Expr, like: &Series) -> Result<Series, EvalError> {
+    match e {
+        Expr::Series(s) => {
+            like.check(&s)?;
+            Ok(*s)
+        }
+        // Products with a series of negative valuation lose precision,
+        // so expand to enough terms to keep the order of `like`.
+        e => {
+            let order = like.order - like.start.min(0);
+            expand(&e, &like.var, &like.point, order)
+        }
+    }
+}
+
+fn into_expr(r: Result<Series, EvalError>) -> Result<Expr, EvalError> {
+    r.map(|s| Expr::Series(Box::new(s)))
+}
+
+/// Expand `e` in `var` around `point` with at least the order term
+/// `O((var - point)^order)` if possible.
+pub fn expand_to(
+    e: &Expr,
+    var: &str,
+    point: &Expr,
+    order: i32,
+) -> Result<Series, EvalError> {
+    let mut work = order;
+    let mut r = expand(e, var, point, work);
+    // Negative powers lose precision in products and quotients,
+    // so expand again with more terms until the requested order is reached.
+    // A quotient whose known coefficients all vanish may just need more
+    // terms as well.
+    for _ in 0..3 {
+        match r {
+            Ok(ref s) if s.order < order => {
+                work += order - s.order;
+            }
+            Err(ref e) if e.message == DIV_ZERO => work += order.max(1),
+            _ => break,
+        }
+        r = expand(e, var, point, work);
+    }
+    r.map(|s| s.truncate(order))
+}
+
+/// Return the series expansion of `e` in `var` around `point` up to the
+/// order term `O((var - point)^order)`.
+pub fn series(
+    e: Expr,
+    var: &str,
+    point: Expr,
+    order: i32,
+) -> Result<Expr, EvalError> {
+    into_expr(expand_to(&e, var, &point, order))
+}
+
+pub fn add(s: Series, e: Expr) -> Result<Expr, EvalError> {
+    into_expr(to_series(e, &s).and_then(|e| s.add(e)))
+}
+
+pub fn mul(s: Series, e: Expr) -> Result<Expr, EvalError> {
+    into_expr(to_series(e, &s).and_then(|e| s.mul(e)))
+}
+
+pub fn pow(s: Series, exp: Num) -> Result<Expr, EvalError> {
+    match exp {
+        Num::Int(i) => into_expr(s.powi(i)),
+        exp => into_expr(s.powr(&exp)),
+    }
+}
+
+pub fn apply(f: Expr, s: Series) -> Result<Expr, EvalError> {
+    into_expr(compose(&f, s))
+}
+
+pub fn lib_series(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    if let [e, Expr::Sym(x), x0, Expr::Num(Num::Int(n))] = args.as_slice() {
+        if *n < 0 {
+            return Err(EvalError::domain("negative order"));
+        }
+        if *n > MAX_ORDER {
+            return Err(EvalError::overflow(format!(
+                "order too large: at most {}",
+                MAX_ORDER
+            )));
+        }
+        if let Expr::Inf(_) = x0 {
+            return Err(EvalError::unsupported("expansion at infinity"));
+        }
+        if !x0.has_sym(x) {
+            return series(e.clone(), x, x0.clone(), *n);
+        }
+    }
+    Err(EvalError::type_mismatch("arguments error"))
+}
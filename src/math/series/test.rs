@@ -0,0 +1,101 @@
+use super::super::utl_test::*;
+
+#[test]
+fn test_series() {
+    let tests = [
+        (r"\series(3, x, 0, 2)", "(3 + O((x ^ 2)))"),
+        (
+            r"\series(1/(1 - x), x, 0, 4)",
+            "(1 + x + (x ^ 2) + (x ^ 3) + O((x ^ 4)))",
+        ),
+        (
+            r"\series(\exp(x), x, 0, 4)",
+            "(1 + x + (1/2 * (x ^ 2)) + (1/6 * (x ^ 3)) + O((x ^ 4)))",
+        ),
+        (
+            r"\series(\sin(x), x, 0, 6)",
+            "(x + (-1/6 * (x ^ 3)) + (1/120 * (x ^ 5)) + O((x ^ 6)))",
+        ),
+        (
+            r"\series(\ln(1 + x), x, 0, 4)",
+            "(x + (-1/2 * (x ^ 2)) + (1/3 * (x ^ 3)) + O((x ^ 4)))",
+        ),
+        (
+            r"\series((1 + x)^(1/2), x, 0, 3)",
+            "(1 + (1/2 * x) + (-1/8 * (x ^ 2)) + O((x ^ 3)))",
+        ),
+        (
+            r"\series(x^2, x, 1, 3)",
+            "(1 + (2 * (x + -1)) + ((x + -1) ^ 2) + O(((x + -1) ^ 3)))",
+        ),
+        (
+            r"\series(\exp(a x), x, 0, 3)",
+            "(1 + (a * x) + (1/2 * (a ^ 2) * (x ^ 2)) + O((x ^ 3)))",
+        ),
+        (r"\series(x^(1/2), x, 0, 3)", "branch point"),
+        (r"\series(\ln(x), x, 0, 3)", "logarithmic singularity"),
+        (r"\series(x, 1, 0, 3)", "arguments error"),
+        (r"\series(\exp(100x), x, 0, 13)", "integer overflow"),
+        (r"\series(\exp(x), x, 0, 14)", "order too large: at most 13"),
+        (
+            r"\series(\sin(x), x, 0, 100)",
+            "order too large: at most 13",
+        ),
+        (r"\series(\sin(x), x, 0, -1)", "negative order"),
+        (r"\series(\sin(x), x, \infty, 3)", "expansion at infinity"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_laurent_series() {
+    let tests = [
+        (r"\series(1/x, x, 0, 3)", "((x ^ -1) + O((x ^ 3)))"),
+        (
+            r"\series(1/\sin(x), x, 0, 4)",
+            "((x ^ -1) + (1/6 * x) + (7/360 * (x ^ 3)) + O((x ^ 4)))",
+        ),
+        (r"\series(\cos(x)/x^2, x, 0, 1)", "((x ^ -2) + -1/2 + O(x))"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_series_arithmetic() {
+    let tests = [
+        (
+            r"\series(\exp(x), x, 0, 3) + x^3",
+            "(1 + x + (1/2 * (x ^ 2)) + O((x ^ 3)))",
+        ),
+        (
+            r"\series(\exp(x), x, 0, 3) - 1",
+            "(x + (1/2 * (x ^ 2)) + O((x ^ 3)))",
+        ),
+        (
+            r"\series(\exp(x), x, 0, 3) * \series(\sin(x), x, 0, 2)",
+            "(x + O((x ^ 2)))",
+        ),
+        (
+            r"\series(\exp(x), x, 0, 3) * \series(\sin(x), x, 0, 4)",
+            "(x + (x ^ 2) + O((x ^ 3)))",
+        ),
+        (
+            r"\sin(\series(x, x, 0, 4))",
+            "(x + (-1/6 * (x ^ 3)) + O((x ^ 4)))",
+        ),
+        (
+            r"\series(x, x, 0, 2) + \series(x, x, 1, 2)",
+            "series around different points",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
@@ -0,0 +1,442 @@
+use std::ops::RangeInclusive;
+
+use super::builtin::{Cmd, Function};
+use super::error::EvalError;
+use super::expr::{Expr, Sign, ONE, ZERO};
+use super::func;
+use super::limit::{self, Dir};
+use super::num::{self, Num};
+use super::poly;
+
+#[cfg(test)]
+mod test;
+
+pub const SUM: Function = Function {
+    name: "\\sum",
+    arity: 4..=4,
+    help: "\\sum(f, k, a, b): sum of f for k from a to b",
+    eval: lib_sum,
+};
+pub const PROD: Function = Function {
+    name: "\\prod",
+    arity: 4..=4,
+    help: "\\prod(f, k, a, b): product of f for k from a to b",
+    eval: lib_prod,
+};
+pub const CMD_SUM: Expr = Expr::Cmd(Cmd(&SUM));
+pub const CMD_PROD: Expr = Expr::Cmd(Cmd(&PROD));
+
+/// Symbol standing for the upper bound while a closed form is factored.
+/// It cannot be produced by the lexer.
+const DUMMY: &str = "\\_";
+
+/// Symbols standing for infinite lower and upper bounds while a closed
+/// form is found.
+const LOWER: &str = "\\_a";
+const UPPER: &str = "\\_b";
+
+/// Maximum number of terms added or multiplied one by one when no closed
+/// form is found.
+const MAX_TERMS: i32 = 10000;
+
+/// Maximum shift between two terms cancelling in a telescoping sum.
+const MAX_SHIFT: i32 = 4;
+
+fn overflow() -> EvalError {
+    EvalError::overflow("integer overflow")
+}
+
+fn binomial(n: i32, k: i32) -> Option<Num> {
+    let mut r = num::ONE;
+    for i in 0..k {
+        r = r.checked_mul(&Num::rational(n - i, i + 1))?;
+    }
+    Some(r)
+}
+
+/// Return the coefficients of `sum_{k=0}^{n} k^p` as a polynomial in `n`
+/// for every `p` up to `max`, obtained from the telescoping identity
+/// `(n + 1)^(p + 1) = sum_{j=0}^{p} C(p + 1, j) sum_{k=0}^{n} k^j`.
+fn power_sums(max: usize) -> Option<Vec<Vec<Num>>> {
+    let mut sums: Vec<Vec<Num>> = Vec::new();
+    for p in 0..=max as i32 {
+        let mut s = (0..=p + 1)
+            .map(|i| binomial(p + 1, i))
+            .collect::<Option<Vec<_>>>()?;
+        for (j, sj) in sums.iter().enumerate() {
+            let c = binomial(p + 1, j as i32)?.checked_mul(&num::NEG_ONE)?;
+            for (i, n) in sj.iter().enumerate() {
+                s[i] = s[i].checked_add(&n.checked_mul(&c)?)?;
+            }
+        }
+        let inv = Num::rational(1, p + 1);
+        let s = s.iter().map(|n| n.checked_mul(&inv)).collect::<Option<_>>();
+        sums.push(s?);
+    }
+    Some(sums)
+}
+
+/// Sum the polynomial with coefficients `c` in the index from `a` to `b`.
+fn sum_poly(c: &[Expr], a: &Expr, b: &Expr) -> Result<Expr, EvalError> {
+    let sums = power_sums(c.len() - 1).ok_or_else(overflow)?;
+    let mut q: Vec<Expr> = Vec::new();
+    for (cp, sp) in c.iter().zip(sums) {
+        for (i, n) in sp.into_iter().enumerate() {
+            let t = Expr::Num(n).try_mul(cp.clone())?;
+            match q.get_mut(i) {
+                Some(e) => *e = std::mem::take(e).try_add(t)?,
+                None => q.push(t),
+            }
+        }
+    }
+    let x = Expr::Sym(DUMMY.to_string());
+    let q0 = poly::eval(&q, &x).try_subs(DUMMY, &(a.clone() - ONE))?;
+    q[0] = std::mem::take(&mut q[0]).try_sub(q0)?;
+    let nums: Option<Vec<Num>> = q
+        .iter()
+        .map(|e| match e {
+            Expr::Num(n) => Some(n.clone()),
+            _ => None,
+        })
+        .collect();
+    let p = match nums {
+        Some(nums) => poly::factor(nums, &x).ok_or_else(overflow)?,
+        None => poly::eval(&q, &x),
+    };
+    p.try_subs(DUMMY, b)
+}
+
+/// Split `e` into a factor free of `var` and a power `base^exp` with `exp`
+/// linear in `var` and `base` free of `var`.
+fn geometric(e: &Expr, var: &str) -> Option<(Expr, Expr, Vec<Expr>)> {
+    let args = match e {
+        Expr::Mul(m) => m.clone().into_args(),
+        e => vec![e.clone()],
+    };
+    let mut c = ONE;
+    let mut pow = None;
+    for e in args {
+        match e {
+            Expr::Pow(base, exp) if !base.has_sym(var) && pow.is_none() => {
+                match poly::coeffs(&exp, var) {
+                    Some(p) if p.len() == 2 => pow = Some((*base, p)),
+                    _ => return None,
+                }
+            }
+            e if !e.has_sym(var) => c *= e,
+            _ => return None,
+        }
+    }
+    pow.map(|(base, p)| (c, base, p))
+}
+
+/// Decompose `c / ((k - p_1) ... (k - p_n))` with distinct `p_i` into
+/// partial fractions and return them with the poles `p_i`.
+fn apart(e: &Expr, var: &str) -> Option<(Vec<Expr>, Vec<Expr>)> {
+    let args = match e {
+        Expr::Mul(m) => m.clone().into_args(),
+        e => vec![e.clone()],
+    };
+    let mut c = ONE;
+    let mut roots = Vec::new();
+    for e in args {
+        match e {
+            Expr::Pow(base, exp) if *exp == Expr::int(-1) => {
+                match poly::coeffs(&base, var) {
+                    Some(ref p) if p.len() == 2 => {
+                        roots.push(Expr::int(-1) * p[0].clone() / p[1].clone());
+                        c = c / p[1].clone();
+                    }
+                    _ => return None,
+                }
+            }
+            e if !e.has_sym(var) => c *= e,
+            _ => return None,
+        }
+    }
+    if roots.len() < 2 {
+        return None;
+    }
+    let mut terms = Vec::new();
+    for (i, p) in roots.iter().enumerate() {
+        let mut w = c.clone();
+        for (j, q) in roots.iter().enumerate() {
+            if i != j {
+                w = w / (p.clone() - q.clone());
+            }
+        }
+        let k = Expr::Sym(var.to_string());
+        terms.push(w * Expr::pow(k - p.clone(), Expr::int(-1)));
+    }
+    Some((terms, roots))
+}
+
+/// Return whether the index may take the value `p` between `a` and `b`,
+/// or `None` if it cannot be told.
+fn crosses(p: &Expr, a: &Expr, b: &Expr) -> Option<bool> {
+    let p = match p {
+        Expr::Num(Num::Int(p)) => *p,
+        Expr::Num(_) => return Some(false),
+        _ => return None,
+    };
+    match (a, b) {
+        (Expr::Num(Num::Int(a)), _) if *a > p => Some(false),
+        (_, Expr::Num(Num::Int(b))) if *b < p => Some(false),
+        (Expr::Num(Num::Int(_)), _) => Some(true),
+        _ => None,
+    }
+}
+
+/// Sum terms `g(k) - g(k + m)` that cancel pairwise.
+fn telescope(terms: Vec<Expr>, var: &str, a: &Expr, b: &Expr) -> Option<Expr> {
+    let k = Expr::Sym(var.to_string());
+    let mut terms = terms;
+    let mut r = ZERO;
+    while let Some(t) = terms.pop() {
+        let mut found = None;
+        'search: for m in 1..=MAX_SHIFT {
+            let shift = k.clone() + Expr::int(m);
+            for (j, u) in terms.iter().enumerate() {
+                if Expr::int(-1) * t.clone().subs(var, &shift) == *u {
+                    found = Some((j, t.clone(), m));
+                } else if Expr::int(-1) * u.clone().subs(var, &shift) == t {
+                    found = Some((j, u.clone(), m));
+                }
+                if found.is_some() {
+                    break 'search;
+                }
+            }
+        }
+        let (j, g, m) = found?;
+        terms.remove(j);
+        for i in 0..m {
+            r += g.clone().subs(var, &(a.clone() + Expr::int(i)));
+            r -= g.clone().subs(var, &(b.clone() + Expr::int(i + 1)));
+        }
+    }
+    Some(r)
+}
+
+fn sum_closed(
+    e: &Expr,
+    var: &str,
+    a: &Expr,
+    b: &Expr,
+) -> Result<Option<Expr>, EvalError> {
+    let terms = match e {
+        Expr::Add(s) => s.clone().into_args(),
+        e => vec![e.clone()],
+    };
+    let mut poly = Vec::new();
+    let mut r = ZERO;
+    let mut rest = Vec::new();
+    for t in terms {
+        if let Some(p) = poly::coeffs(&t, var) {
+            poly = poly::add(&poly, &p);
+        } else if let Some((c, base, p)) = geometric(&t, var) {
+            // sum_{k=a}^{b} c base^(p_0 + p_1 k)
+            let ratio = Expr::pow(base.clone(), p[1].clone());
+            let c = c * Expr::pow(base, p[0].clone());
+            if ratio == ONE {
+                poly = poly::add(&poly, &[c]);
+                continue;
+            }
+            let upper = ratio.clone().try_pow(b.clone() + ONE)?;
+            let lower = ratio.clone().try_pow(a.clone())?;
+            r = r.try_add(c.try_mul((upper - lower).try_div(ratio - ONE)?)?)?;
+        } else if let Some((p, poles)) = apart(&t, var) {
+            for pole in &poles {
+                match crosses(pole, a, b) {
+                    Some(false) => {}
+                    Some(true) => {
+                        return Err(EvalError::domain("division by zero"))
+                    }
+                    None => return Ok(None),
+                }
+            }
+            rest.extend(p);
+        } else {
+            rest.push(t);
+        }
+    }
+    if !poly.is_empty() {
+        r = r.try_add(sum_poly(&poly, a, b)?)?;
+    }
+    match telescope(rest, var, a, b) {
+        Some(t) => Ok(Some(r.try_add(t)?)),
+        None => Ok(None),
+    }
+}
+
+fn prod_closed(
+    e: &Expr,
+    var: &str,
+    a: &Expr,
+    b: &Expr,
+) -> Result<Option<Expr>, EvalError> {
+    if !e.has_sym(var) {
+        return Ok(Some(Expr::pow(e.clone(), b.clone() - a.clone() + ONE)));
+    }
+    match e {
+        Expr::Mul(m) => {
+            let mut r = ONE;
+            for e in m.clone().into_args() {
+                match prod_closed(&e, var, a, b)? {
+                    Some(p) => r = r.try_mul(p)?,
+                    None => return Ok(None),
+                }
+            }
+            Ok(Some(r))
+        }
+        Expr::Pow(base, exp) if !exp.has_sym(var) => {
+            let p = prod_closed(base, var, a, b)?;
+            Ok(p.map(|p| Expr::pow(p, *exp.clone())))
+        }
+        Expr::Pow(base, exp) if !base.has_sym(var) => {
+            let s = sum_closed(exp, var, a, b)?;
+            Ok(s.map(|s| Expr::pow(*base.clone(), s)))
+        }
+        e => match poly::coeffs(e, var) {
+            // prod_{k=a}^{b} c (k + m) = c^(b - a + 1) (b + m)! / (a + m - 1)!
+            // if every factor k + m is positive.
+            Some(ref p) if p.len() == 2 => {
+                let c = p[1].clone();
+                let m = p[0].clone() / c.clone();
+                if let Expr::Num(Num::Rat(..)) = m {
+                    return Ok(None);
+                }
+                match (a.clone() + m.clone(), b.clone() + m.clone()) {
+                    (Expr::Num(Num::Int(i)), _) if i >= 1 => {
+                        let n = b.clone() - a.clone() + ONE;
+                        let upper = func::factorial(b.clone() + m.clone());
+                        let lower = func::factorial(a.clone() + m - ONE);
+                        Ok(Some(Expr::pow(c, n) * upper / lower))
+                    }
+                    // The factor of k = -m is zero.
+                    (Expr::Num(Num::Int(i)), Expr::Num(Num::Int(j)))
+                        if i <= 0 && j >= 0 =>
+                    {
+                        Ok(Some(ZERO))
+                    }
+                    _ => Ok(None),
+                }
+            }
+            _ => Ok(None),
+        },
+    }
+}
+
+type Closed = fn(&Expr, &str, &Expr, &Expr) -> Result<Option<Expr>, EvalError>;
+
+/// Return the bounds if both are integers, or `None` if either is
+/// symbolic or infinite.
+fn bounds(a: &Expr, b: &Expr) -> Result<Option<(i32, i32)>, EvalError> {
+    match (a, b) {
+        (Expr::Num(Num::Rat(..)), _) | (_, Expr::Num(Num::Rat(..))) => {
+            Err(EvalError::domain("non-integer bound"))
+        }
+        (Expr::Num(Num::Int(a)), Expr::Num(Num::Int(b))) => Ok(Some((*a, *b))),
+        _ => Ok(None),
+    }
+}
+
+/// Return whether there are no terms between `a` and `b`.
+fn is_empty(a: &Expr, b: &Expr) -> Result<bool, EvalError> {
+    match (a, b) {
+        (Expr::Inf(Sign::Pos), _) | (_, Expr::Inf(Sign::Neg)) => Ok(true),
+        (a, b) => Ok(matches!(bounds(a, b)?, Some((i, j)) if j < i)),
+    }
+}
+
+/// Find a closed form with `closed`, taking infinite bounds as limits of
+/// the closed form with symbolic bounds.
+fn closed_form(
+    e: &Expr,
+    var: &str,
+    a: &Expr,
+    b: &Expr,
+    closed: Closed,
+) -> Result<Option<Expr>, EvalError> {
+    let symbolic = |bound: &Expr, sym: &str| match bound {
+        Expr::Inf(_) => Expr::Sym(sym.to_string()),
+        bound => bound.clone(),
+    };
+    let (a1, b1) = (symbolic(a, LOWER), symbolic(b, UPPER));
+    let mut r = match closed(e, var, &a1, &b1)? {
+        Some(r) => r,
+        None => return Ok(None),
+    };
+    if let Expr::Inf(_) = b {
+        r = limit::limit(r, UPPER, b.clone(), Dir::Both)?;
+    }
+    if let Expr::Inf(_) = a {
+        r = limit::limit(r, LOWER, a.clone(), Dir::Both)?;
+    }
+    Ok(Some(r))
+}
+
+/// Return the indices from `i` to `j` if there are few enough to add or
+/// multiply the terms one by one.
+fn terms(i: i32, j: i32) -> Option<RangeInclusive<i32>> {
+    if i64::from(j) - i64::from(i) < i64::from(MAX_TERMS) {
+        Some(i..=j)
+    } else {
+        None
+    }
+}
+
+pub fn sum(e: Expr, var: &str, a: Expr, b: Expr) -> Result<Expr, EvalError> {
+    if is_empty(&a, &b)? {
+        return Ok(ZERO);
+    }
+    if let Some(r) = closed_form(&e, var, &a, &b, sum_closed)? {
+        return Ok(r);
+    }
+    match bounds(&a, &b)?.and_then(|(i, j)| terms(i, j)) {
+        Some(mut ks) => ks.try_fold(ZERO, |r, k| {
+            r.try_add(e.clone().try_subs(var, &Expr::int(k))?)
+        }),
+        None => Ok(Expr::unevaluated_call(
+            CMD_SUM,
+            Expr::Vec(vec![e, Expr::Sym(var.to_string()), a, b]),
+        )),
+    }
+}
+
+pub fn prod(e: Expr, var: &str, a: Expr, b: Expr) -> Result<Expr, EvalError> {
+    if is_empty(&a, &b)? {
+        return Ok(ONE);
+    }
+    if let Some(r) = closed_form(&e, var, &a, &b, prod_closed)? {
+        return Ok(r);
+    }
+    match bounds(&a, &b)?.and_then(|(i, j)| terms(i, j)) {
+        Some(mut ks) => ks.try_fold(ONE, |r, k| {
+            r.try_mul(e.clone().try_subs(var, &Expr::int(k))?)
+        }),
+        None => Ok(Expr::unevaluated_call(
+            CMD_PROD,
+            Expr::Vec(vec![e, Expr::Sym(var.to_string()), a, b]),
+        )),
+    }
+}
+
+fn lib_sum_prod(
+    args: Vec<Expr>,
+    f: fn(Expr, &str, Expr, Expr) -> Result<Expr, EvalError>,
+) -> Result<Expr, EvalError> {
+    if let [e, Expr::Sym(k), a, b] = args.as_slice() {
+        if !a.has_sym(k) && !b.has_sym(k) {
+            return f(e.clone(), k, a.clone(), b.clone());
+        }
+    }
+    Err(EvalError::type_mismatch("arguments error"))
+}
+
+pub fn lib_sum(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    lib_sum_prod(args, sum)
+}
+
+pub fn lib_prod(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    lib_sum_prod(args, prod)
+}
@@ -0,0 +1,128 @@
+use super::super::utl_test::*;
+
+#[test]
+fn test_sum() {
+    let tests = [
+        (r"\sum(1, k, 1, n)", "n"),
+        (r"\sum(k, k, 1, n)", "(1/2 * n * (n + 1))"),
+        (r"\sum(k^2, k, 1, n)", "(1/6 * n * (n + 1) * ((2 * n) + 1))"),
+        (r"\sum(k^3, k, 1, n)", "(1/4 * (n ^ 2) * ((n + 1) ^ 2))"),
+        (
+            r"\sum(a k + b, k, 1, n)",
+            "((1/2 * a * (n ^ 2)) + (n * ((1/2 * a) + b)))",
+        ),
+        (r"\sum(k^2, k, 1, 10)", "385"),
+        (r"\sum(k, k, 3, 1)", "0"),
+        (r"\sum(k, 1, 1, n)", "arguments error"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_geometric_sum() {
+    let tests = [
+        (r"\sum(2^k, k, 0, n)", "((2 ^ (n + 1)) + -1)"),
+        (r"\sum(3 * 2^(k+1), k, 0, n)", "(6 * ((2 ^ (n + 1)) + -1))"),
+        (
+            r"\sum(r^k, k, 0, n)",
+            "(((r + -1) ^ -1) * ((r ^ (n + 1)) + -1))",
+        ),
+        (r"\sum(2^k, k, 0, 4)", "31"),
+        (r"\sum((-1)^(2k), k, 0, 3)", "4"),
+        (r"\sum(3 * 1^k, k, 1, n)", "(3 * n)"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_telescoping_sum() {
+    let tests = [
+        (
+            r"\sum(1/k - 1/(k+1), k, 1, n)",
+            "((-1 * ((n + 1) ^ -1)) + 1)",
+        ),
+        (r"\sum(1/(k(k+1)), k, 1, n)", "((-1 * ((n + 1) ^ -1)) + 1)"),
+        (
+            r"\sum(1/(k(k+2)), k, 1, n)",
+            "((-1/2 * ((n + 1) ^ -1)) + (-1/2 * ((n + 2) ^ -1)) + 3/4)",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_numeric_sum() {
+    let tests = [
+        (r"\sum(1/k, k, 1, 4)", "25/12"),
+        (r"\sum(1/k, k, 1, n)", r"\sum(((k ^ -1), k, 1, n))"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_prod() {
+    let tests = [
+        (r"\prod(k, k, 1, n)", r"\factorial(n)"),
+        (r"\prod(k, k, 1, 5)", "120"),
+        (r"\prod(k + 1, k, 1, n)", r"\factorial((n + 1))"),
+        (r"\prod(2k, k, 1, n)", r"((2 ^ n) * \factorial(n))"),
+        (r"\prod(a, k, 1, n)", "(a ^ n)"),
+        (r"\prod(2^k, k, 1, n)", "(2 ^ (1/2 * n * (n + 1)))"),
+        (r"\prod(k^2 + 1, k, 1, 3)", "100"),
+        (r"\prod(k, k, 2, 1)", "1"),
+        (r"\prod(k, k, 0, 5)", "0"),
+        (r"\prod(k, k, -3, -1)", "-6"),
+        (r"\prod(k - 2, k, 1, 5)", "0"),
+        (r"\prod(k, k, 0, n)", r"\prod((k, k, 0, n))"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_infinite_sum() {
+    let tests = [
+        (r"\sum(1/2^k, k, 0, \infty)", "2"),
+        (r"\sum(2^k, k, -\infty, 0)", "2"),
+        (r"\sum(k, k, 1, \infty)", r"\infty"),
+        (r"\sum(1/(k(k+1)), k, 1, \infty)", "1"),
+        (r"\sum((-1)^k, k, 0, \infty)", "limit does not exist"),
+        (r"\sum(k, k, 1, -\infty)", "0"),
+        (r"\prod(2, k, \infty, 1)", "1"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_sum_error() {
+    let tests = [
+        (r"\sum(k, k, 1, 1/2)", "non-integer bound"),
+        (r"\prod(k, k, 1/2, 3)", "non-integer bound"),
+        (r"\sum(k, k, 1, 100000)", "integer overflow"),
+        (r"\sum(k^30, k, 1, n)", "integer overflow"),
+        (r"\sum(1/(k(k+1)), k, 0, n)", "division by zero"),
+        (r"\sum(1/(k(k+1)), k, -3, 2)", "division by zero"),
+        (r"\prod(k, k, 1, 20)", r"\factorial(20)"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
@@ -0,0 +1,701 @@
+use super::builtin::{Cmd, Function};
+use super::diff::{diff, partial};
+use super::error::EvalError;
+use super::expr::{Expr, NEG_ONE, ONE, ZERO};
+use super::func;
+use super::limit::CMD_LIMIT;
+use super::num::{self, Num};
+use super::poly;
+
+#[cfg(test)]
+mod test;
+
+pub const LAPLACE: Function = Function {
+    name: "\\laplace",
+    arity: 3..=3,
+    help: "\\laplace(f, t, s): Laplace transform of f",
+    eval: lib_laplace,
+};
+pub const INVLAPLACE: Function = Function {
+    name: "\\invlaplace",
+    arity: 3..=3,
+    help: "\\invlaplace(F, s, t): inverse Laplace transform of F",
+    eval: lib_invlaplace,
+};
+pub const FOURIER: Function = Function {
+    name: "\\fourier",
+    arity: 3..=3,
+    help: "\\fourier(f, t, w): Fourier transform of f",
+    eval: lib_fourier,
+};
+pub const INVFOURIER: Function = Function {
+    name: "\\invfourier",
+    arity: 3..=3,
+    help: "\\invfourier(F, w, t): inverse Fourier transform of F",
+    eval: lib_invfourier,
+};
+pub const CMD_LAPLACE: Expr = Expr::Cmd(Cmd(&LAPLACE));
+pub const CMD_INVLAPLACE: Expr = Expr::Cmd(Cmd(&INVLAPLACE));
+pub const CMD_FOURIER: Expr = Expr::Cmd(Cmd(&FOURIER));
+pub const CMD_INVFOURIER: Expr = Expr::Cmd(Cmd(&INVFOURIER));
+
+const PI: &str = "\\pi";
+/// The imaginary unit, which only appears in Fourier transforms.
+const I: &str = "\\i";
+const UNSUPPORTED: &str = "unsupported transform";
+
+fn err() -> EvalError {
+    EvalError::unsupported(UNSUPPORTED)
+}
+
+fn args(e: Expr) -> Vec<Expr> {
+    match e {
+        Expr::Add(a) => a.into_args(),
+        e => vec![e],
+    }
+}
+
+fn factors(e: Expr) -> Vec<Expr> {
+    match e {
+        Expr::Mul(m) => m.into_args(),
+        e => vec![e],
+    }
+}
+
+/// Return `g` if `e` is the unevaluated derivative `\diff(g, t)`.
+fn diff_arg<'a>(e: &'a Expr, t: &str) -> Option<&'a Expr> {
+    match e {
+        Expr::Call(f, args) if f.is_cmd("\\diff") => match **args {
+            Expr::Vec(ref v) => match v.as_slice() {
+                [g, Expr::Sym(x)] if x == t => Some(g),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Collect the symbols differentiated with respect to `t` in `e`. They
+/// are the unknown functions of `t`; every other symbol is a constant.
+fn unknowns(e: &Expr, t: &str, r: &mut Vec<String>) {
+    if let Some(g) = diff_arg(e, t) {
+        match g {
+            Expr::Sym(y) if !r.contains(y) => r.push(y.clone()),
+            g => unknowns(g, t, r),
+        }
+        return;
+    }
+    match e {
+        Expr::Add(_) | Expr::Mul(_) => {
+            for e in args(e.clone()) {
+                for e in factors(e) {
+                    unknowns(&e, t, r);
+                }
+            }
+        }
+        Expr::Pow(b, n) => {
+            unknowns(b, t, r);
+            unknowns(n, t, r);
+        }
+        Expr::Vec(v) => v.iter().for_each(|e| unknowns(e, t, r)),
+        Expr::Call(_, x) => unknowns(x, t, r),
+        _ => {}
+    }
+}
+
+fn depends(e: &Expr, t: &str, funcs: &[String]) -> bool {
+    e.has_sym(t) || funcs.iter().any(|y| e.has_sym(y))
+}
+
+/// Return `g(0+)` for an unknown function `g` of `t`.
+fn initial(g: Expr, t: &str) -> Expr {
+    Expr::unevaluated_call(
+        CMD_LIMIT,
+        Expr::Vec(vec![g, Expr::Sym(t.to_string()), ZERO, ONE]),
+    )
+}
+
+/// Return `y` if `e` is the unevaluated transform `cmd(y, _, s)`.
+fn transformed<'a>(e: &'a Expr, cmd: &str, s: &str) -> Option<&'a Expr> {
+    match e {
+        Expr::Call(f, args) => match (&**f, &**args) {
+            (Expr::Cmd(c), Expr::Vec(v)) if c.name() == cmd => {
+                match v.as_slice() {
+                    [y, Expr::Sym(_), Expr::Sym(x)] if x == s => Some(y),
+                    _ => None,
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// An unknown function and the polynomial multiplying its transform.
+type Unknown = (Expr, Vec<Expr>);
+
+/// Split the terms of `e` into the polynomials in `s` multiplying the
+/// unevaluated transforms `cmd(y, _, s)` and the remaining terms.
+fn split_unknowns(
+    e: Expr,
+    cmd: &str,
+    s: &str,
+) -> Result<(Vec<Unknown>, Expr), EvalError> {
+    let mut ys: Vec<Unknown> = Vec::new();
+    let mut rest = ZERO;
+    for term in args(e) {
+        let fs = factors(term.clone());
+        let y = match fs.iter().find_map(|f| transformed(f, cmd, s)) {
+            Some(y) => y.clone(),
+            None => {
+                rest += term;
+                continue;
+            }
+        };
+        let c = Expr::prod(
+            fs.into_iter().filter(|f| transformed(f, cmd, s).is_none()),
+        );
+        let p = poly::coeffs(&c, s).ok_or_else(err)?;
+        match ys.iter_mut().find(|(z, _)| *z == y) {
+            Some((_, q)) => *q = poly::add(q, &p),
+            None => ys.push((y, p)),
+        }
+    }
+    Ok((ys, rest))
+}
+
+/// Return the coefficient of `t` if `e` is linear in `t`, and the
+/// constant term.
+fn linear(e: &Expr, t: &str) -> Option<(Expr, Expr)> {
+    match poly::coeffs(e, t)?.as_slice() {
+        [b, a] => Some((a.clone(), b.clone())),
+        _ => None,
+    }
+}
+
+fn laplace_of(
+    f: Expr,
+    t: &str,
+    s: &str,
+    funcs: &[String],
+) -> Result<Expr, EvalError> {
+    let ss = Expr::Sym(s.to_string());
+    if !depends(&f, t, funcs) {
+        return Ok(f / ss);
+    }
+    // L{g'} = s G - g(0+)
+    if let Some(g) = diff_arg(&f, t) {
+        let g = g.clone();
+        return Ok(ss * laplace_of(g.clone(), t, s, funcs)? - initial(g, t));
+    }
+    let (c, fs): (Vec<Expr>, Vec<Expr>) = match f {
+        Expr::Sym(ref y) if funcs.contains(y) => {
+            let args = vec![f.clone(), Expr::Sym(t.to_string()), ss];
+            return Ok(Expr::unevaluated_call(CMD_LAPLACE, Expr::Vec(args)));
+        }
+        Expr::Add(a) => {
+            let mut r = Vec::new();
+            for e in a.into_args() {
+                r.push(laplace_of(e, t, s, funcs)?);
+            }
+            return Ok(Expr::sum(r));
+        }
+        f => factors(f).into_iter().partition(|e| !depends(e, t, funcs)),
+    };
+    if let [f] = fs.as_slice() {
+        if diff_arg(f, t).is_some()
+            || matches!(f, Expr::Sym(y) if funcs.contains(y))
+        {
+            return Ok(Expr::prod(c) * laplace_of(f.clone(), t, s, funcs)?);
+        }
+    }
+
+    // f = c t^n exp(a t) sin(b t) or f = c t^n exp(a t) cos(b t)
+    let (mut n, mut a, mut wave) = (0, ZERO, None);
+    let mut c = Expr::prod(c);
+    for f in fs {
+        match f {
+            Expr::Sym(ref x) if x == t => n += 1,
+            Expr::Pow(ref b, ref k) if **b == Expr::Sym(t.to_string()) => {
+                match **k {
+                    Expr::Num(Num::Int(k)) if k > 0 => n += k,
+                    _ => return Err(err()),
+                }
+            }
+            Expr::Call(g, u) => {
+                let (k, b) = linear(&u, t).ok_or_else(err)?;
+                match g.cmd_name() {
+                    Some("\\exp") => {
+                        a += k;
+                        c *= func::exp(b);
+                    }
+                    Some(name @ ("\\sin" | "\\cos"))
+                        if b == ZERO && wave.is_none() =>
+                    {
+                        wave = Some((name, k))
+                    }
+                    _ => return Err(err()),
+                }
+            }
+            _ => return Err(err()),
+        }
+    }
+    let mut r = match wave {
+        None => Expr::pow(ss.clone(), NEG_ONE),
+        Some((name, b)) => {
+            let q = Expr::pow(ss.clone(), Expr::int(2))
+                + Expr::pow(b.clone(), Expr::int(2));
+            let p = if name == "\\sin" { b } else { ss.clone() };
+            p * Expr::pow(q, NEG_ONE)
+        }
+    };
+    // L{t^n f} = (-1)^n F^(n)(s)
+    for _ in 0..n {
+        r = NEG_ONE * partial(r, s);
+    }
+    // L{exp(a t) f} = F(s - a)
+    if a != ZERO {
+        r = r.subs(s, &(ss - a));
+    }
+    Ok(c * r)
+}
+
+/// The factors of the denominator of a proper rational function in `s`.
+#[derive(Default)]
+struct Denominator {
+    lc: Expr,
+    /// Roots with multiplicity.
+    linear: Vec<(Expr, usize)>,
+    /// Triples `(a, b, m)` for the factors `((s - a)^2 + b^2)^m`.
+    quadratic: Vec<(Expr, Expr, usize)>,
+}
+
+impl Denominator {
+    fn push_linear(&mut self, r: Expr, k: usize) {
+        match self.linear.iter_mut().find(|(q, _)| *q == r) {
+            Some((_, m)) => *m += k,
+            None => self.linear.push((r, k)),
+        }
+    }
+
+    fn push_quadratic(
+        &mut self,
+        p: &[Expr],
+        k: usize,
+    ) -> Result<(), EvalError> {
+        let half = Expr::Num(Num::rational(1, 2));
+        let a = NEG_ONE * half.clone() * p[1].clone() / p[2].clone();
+        let b2 = (p[0].clone() / p[2].clone() - a.clone() * a.clone()).expand();
+        match b2 {
+            // Irrational real roots a +- r.
+            Expr::Num(ref n) if n.signum() < 0 => {
+                let r = Expr::pow(NEG_ONE * b2.clone(), half);
+                self.push_linear(a.clone() + r.clone(), k);
+                self.push_linear(a - r, k);
+                Ok(())
+            }
+            _ if k > 2 => Err(err()),
+            b2 => {
+                self.quadratic.push((a, Expr::pow(b2, half), k));
+                Ok(())
+            }
+        }
+    }
+
+    /// Add the factor `base^k` with a polynomial `base` in `s`.
+    fn push(
+        &mut self,
+        base: &Expr,
+        k: usize,
+        s: &str,
+    ) -> Result<(), EvalError> {
+        let p = poly::coeffs(base, s).ok_or_else(err)?;
+        let lc = p.last().cloned().unwrap_or(ZERO);
+        self.lc *= Expr::pow(lc, Expr::int(k as i32));
+        let nums: Option<Vec<Num>> = p
+            .iter()
+            .map(|c| match c {
+                Expr::Num(n) => Some(n.clone()),
+                _ => None,
+            })
+            .collect();
+        match (nums, p.len()) {
+            (_, 2) => {
+                let r = NEG_ONE * p[0].clone() / p[1].clone();
+                self.push_linear(r, k);
+                Ok(())
+            }
+            (None, 3) => self.push_quadratic(&p, k),
+            (Some(nums), _) => {
+                let (roots, rest) = poly::rational_roots(nums);
+                for r in roots {
+                    self.push_linear(Expr::Num(r), k);
+                }
+                let rest: Vec<Expr> = rest.into_iter().map(Expr::Num).collect();
+                match rest.len() {
+                    1 => Ok(()),
+                    3 => self.push_quadratic(&rest, k),
+                    _ => Err(err()),
+                }
+            }
+            _ => Err(err()),
+        }
+    }
+
+    fn degree(&self) -> usize {
+        self.linear.iter().map(|(_, m)| m).sum::<usize>()
+            + self.quadratic.iter().map(|(_, _, m)| 2 * m).sum::<usize>()
+    }
+
+    /// Return the monic denominator as an expression in `s`.
+    fn monic(&self, s: &Expr) -> Expr {
+        let l = self.linear.iter().map(|(r, m)| {
+            Expr::pow(s.clone() - r.clone(), Expr::int(*m as i32))
+        });
+        let q = self.quadratic.iter().map(|(a, b, m)| {
+            Expr::pow(quadratic(a, b, s), Expr::int(*m as i32))
+        });
+        Expr::prod(l.chain(q))
+    }
+}
+
+fn quadratic(a: &Expr, b: &Expr, s: &Expr) -> Expr {
+    let two = Expr::int(2);
+    (Expr::pow(s.clone() - a.clone(), two.clone()) + Expr::pow(b.clone(), two))
+        .expand()
+}
+
+/// Invert a single term `P(s) / Q(s)` of a transform by partial fractions.
+fn invlaplace_term(term: Expr, s: &str, t: &str) -> Result<Expr, EvalError> {
+    let ss = Expr::Sym(s.to_string());
+    let ts = Expr::Sym(t.to_string());
+    let mut num = vec![ONE];
+    let mut den = Denominator {
+        lc: ONE,
+        ..Default::default()
+    };
+    for f in factors(term) {
+        match f {
+            Expr::Pow(ref b, ref k) if b.has_sym(s) => match **k {
+                Expr::Num(Num::Int(k)) if k < 0 => {
+                    den.push(b, k.unsigned_abs() as usize, s)?
+                }
+                _ => {
+                    num = poly::mul(&num, &poly::coeffs(&f, s).ok_or_else(err)?)
+                }
+            },
+            f => num = poly::mul(&num, &poly::coeffs(&f, s).ok_or_else(err)?),
+        }
+    }
+    let d = den.degree();
+    if num.len() > d {
+        return Err(EvalError::unsupported("not a proper rational function"));
+    }
+
+    let monic = den.monic(&ss);
+    let mut basis = Vec::new();
+    for (r, m) in &den.linear {
+        for k in 1..=*m {
+            let p = Expr::pow(ss.clone() - r.clone(), Expr::int(-(k as i32)));
+            basis.push(monic.clone() * p);
+        }
+    }
+    for (a, b, m) in &den.quadratic {
+        for k in 1..=*m {
+            let q = Expr::pow(quadratic(a, b, &ss), Expr::int(-(k as i32)));
+            basis.push(ss.clone() * monic.clone() * q.clone());
+            basis.push(monic.clone() * q);
+        }
+    }
+    let mut rows = vec![Vec::new(); d];
+    for b in &basis {
+        let p = poly::coeffs(&b.clone().expand(), s).ok_or_else(err)?;
+        for (i, row) in rows.iter_mut().enumerate() {
+            row.push(p.get(i).cloned().unwrap_or(ZERO));
+        }
+    }
+    let rhs = (0..d)
+        .map(|i| num.get(i).cloned().unwrap_or(ZERO) / den.lc.clone())
+        .collect();
+    let c = poly::solve_linear(rows, rhs).ok_or_else(err)?;
+
+    let mut c = c.into_iter();
+    let mut r = ZERO;
+    for (root, m) in &den.linear {
+        for k in 1..=*m {
+            // 1 / (s - r)^k -> t^(k-1) exp(r t) / (k-1)!
+            let a = c.next().unwrap();
+            let p = Expr::pow(ts.clone(), Expr::int(k as i32 - 1));
+            let f = func::factorial(Expr::int(k as i32 - 1));
+            r += a * p * func::exp(root.clone() * ts.clone()) / f;
+        }
+    }
+    for (a, b, m) in &den.quadratic {
+        let e = func::exp(a.clone() * ts.clone());
+        let bt = b.clone() * ts.clone();
+        let (sin, cos) = (func::sin(bt.clone()), func::cos(bt.clone()));
+        for k in 1..=*m {
+            // p s + q = p (s - a) + (q + p a)
+            let (p, q) = (c.next().unwrap(), c.next().unwrap());
+            let q = q + p.clone() * a.clone();
+            let f = if k == 1 {
+                // (s - a) / Q -> exp(a t) cos(b t)
+                // 1 / Q -> exp(a t) sin(b t) / b
+                p * cos.clone() + q / b.clone() * sin.clone()
+            } else {
+                // (s - a) / Q^2 -> exp(a t) t sin(b t) / (2 b)
+                // 1 / Q^2 -> exp(a t) (sin(b t) - b t cos(b t)) / (2 b^3)
+                let b3 = Expr::pow(b.clone(), Expr::int(3));
+                p * ts.clone() * sin.clone() / (Expr::int(2) * b.clone())
+                    + q * (sin.clone() - bt.clone() * cos.clone())
+                        / (Expr::int(2) * b3)
+            };
+            r += e.clone() * f;
+        }
+    }
+    Ok(r.expand())
+}
+
+fn invlaplace_of(f: Expr, s: &str, t: &str) -> Result<Expr, EvalError> {
+    let (ys, mut rest) = split_unknowns(f.expand(), "\\laplace", s)?;
+    let ss = Expr::Sym(s.to_string());
+    let mut r = ZERO;
+    for (y, p) in ys {
+        // s^k Y = L{y^(k)} + sum_{j<k} s^(k-1-j) y^(j)(0+)
+        let mut ds = vec![y];
+        for _ in 1..p.len() {
+            let d = diff(ds[ds.len() - 1].clone(), t);
+            ds.push(d);
+        }
+        for (k, pk) in p.iter().enumerate() {
+            r += pk.clone() * ds[k].clone();
+            for (j, d) in ds.iter().enumerate().take(k) {
+                let sp = Expr::pow(ss.clone(), Expr::int((k - 1 - j) as i32));
+                rest += pk.clone() * sp * initial(d.clone(), t);
+            }
+        }
+    }
+    for term in args(rest.expand()) {
+        if term != ZERO {
+            r += invlaplace_term(term, s, t)?;
+        }
+    }
+    Ok(r)
+}
+
+/// Replace the powers of the imaginary unit by `1`, `i`, `-1` or `-i`.
+fn reduce_i(e: Expr) -> Expr {
+    match e {
+        Expr::Pow(b, n) => match (*b, *n) {
+            (Expr::Sym(ref s), Expr::Num(Num::Int(n))) if s == I => {
+                let i = Expr::Sym(I.to_string());
+                [ONE, i.clone(), NEG_ONE, NEG_ONE * i][n.rem_euclid(4) as usize]
+                    .clone()
+            }
+            (b, n) => Expr::pow(reduce_i(b), reduce_i(n)),
+        },
+        Expr::Add(a) => Expr::sum(a.into_args().into_iter().map(reduce_i)),
+        Expr::Mul(m) => Expr::prod(m.into_args().into_iter().map(reduce_i)),
+        Expr::Call(f, x) => match *f {
+            Expr::Cmd(c) if func::is_elementary(c.name()) => {
+                Expr::call(*f, reduce_i(*x))
+            }
+            f => Expr::unevaluated_call(f, reduce_i(*x)),
+        },
+        e => e,
+    }
+}
+
+/// Return `a` if `e` is `exp(-a v^2)`.
+fn gaussian(e: &Expr, v: &str) -> Option<Expr> {
+    match e {
+        Expr::Call(f, u) if f.is_cmd("\\exp") => {
+            match poly::coeffs(u, v)?.as_slice() {
+                [_, b, a] if *b == ZERO => Some(NEG_ONE * a.clone()),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Split `f` into the constant factor, the power of `v` and `a` for a
+/// Gaussian factor `exp(-a v^2)`.
+fn gaussian_term(
+    f: Expr,
+    v: &str,
+    funcs: &[String],
+) -> Result<(Expr, i32, Expr), EvalError> {
+    let (mut c, mut n, mut a) = (ONE, 0, None);
+    for f in factors(f) {
+        if !depends(&f, v, funcs) {
+            c *= f;
+            continue;
+        }
+        match f {
+            Expr::Sym(ref x) if x == v => n += 1,
+            Expr::Pow(ref b, ref k) if **b == Expr::Sym(v.to_string()) => {
+                match **k {
+                    Expr::Num(Num::Int(k)) if k > 0 => n += k,
+                    _ => return Err(err()),
+                }
+            }
+            Expr::Call(ref g, ref u) if g.is_cmd("\\exp") && a.is_none() => {
+                let p = poly::coeffs(u, v).ok_or_else(err)?;
+                a = Some(gaussian(&f, v).ok_or_else(err)?);
+                c *= func::exp(p[0].clone());
+            }
+            _ => return Err(err()),
+        }
+    }
+    Ok((c, n, a.ok_or_else(err)?))
+}
+
+fn fourier_of(
+    f: Expr,
+    t: &str,
+    w: &str,
+    funcs: &[String],
+) -> Result<Expr, EvalError> {
+    let i = Expr::Sym(I.to_string());
+    let ws = Expr::Sym(w.to_string());
+    // F{g'} = i w G
+    if let Some(g) = diff_arg(&f, t) {
+        return Ok(i * ws * fourier_of(g.clone(), t, w, funcs)?);
+    }
+    match f {
+        Expr::Sym(ref y) if funcs.contains(y) => {
+            let args = vec![f.clone(), Expr::Sym(t.to_string()), ws];
+            Ok(Expr::unevaluated_call(CMD_FOURIER, Expr::Vec(args)))
+        }
+        Expr::Add(a) => {
+            let mut r = Vec::new();
+            for e in a.into_args() {
+                r.push(fourier_of(e, t, w, funcs)?);
+            }
+            Ok(Expr::sum(r))
+        }
+        f => {
+            let (c, fs): (Vec<Expr>, Vec<Expr>) =
+                factors(f).into_iter().partition(|e| !depends(e, t, funcs));
+            if let [f] = fs.as_slice() {
+                if diff_arg(f, t).is_some()
+                    || matches!(f, Expr::Sym(y) if funcs.contains(y))
+                {
+                    return Ok(
+                        Expr::prod(c) * fourier_of(f.clone(), t, w, funcs)?
+                    );
+                }
+            }
+            let (c, n, a) =
+                gaussian_term(Expr::prod(c) * Expr::prod(fs), t, funcs)?;
+            // F{exp(-a t^2)} = sqrt(pi / a) exp(-w^2 / (4 a))
+            let half = Expr::Num(Num::rational(1, 2));
+            let w2 = Expr::pow(ws, Expr::int(2));
+            let mut r = Expr::pow(Expr::Sym(PI.to_string()) / a.clone(), half)
+                * func::exp(NEG_ONE * w2 / (Expr::int(4) * a));
+            // F{t^n f} = i^n F^(n)(w)
+            for _ in 0..n {
+                r = i.clone() * partial(r, w);
+            }
+            Ok(c * r)
+        }
+    }
+}
+
+fn invfourier_of(f: Expr, w: &str, t: &str) -> Result<Expr, EvalError> {
+    let (ys, rest) = split_unknowns(reduce_i(f.expand()), "\\fourier", w)?;
+    let minus_i = NEG_ONE * Expr::Sym(I.to_string());
+    let mut r = ZERO;
+    for (y, p) in ys {
+        // w^k Y = (-i)^k F{y^(k)}
+        let mut d = y;
+        for (k, pk) in p.into_iter().enumerate() {
+            r += pk
+                * Expr::pow(minus_i.clone(), Expr::int(k as i32))
+                * d.clone();
+            d = diff(d, t);
+        }
+    }
+    for term in args(rest) {
+        if term == ZERO {
+            continue;
+        }
+        let (c, n, b) = gaussian_term(term, w, &[])?;
+        // F^-1{exp(-b w^2)} = exp(-t^2 / (4 b)) / (2 sqrt(pi b))
+        let half = Expr::Num(Num::rational(1, 2));
+        let t2 = Expr::pow(Expr::Sym(t.to_string()), Expr::int(2));
+        let mut g = half.clone()
+            * Expr::pow(Expr::Sym(PI.to_string()) * b.clone(), NEG_ONE * half)
+            * func::exp(NEG_ONE * t2 / (Expr::int(4) * b));
+        // F^-1{w^n G} = (-i)^n g^(n)(t)
+        for _ in 0..n {
+            g = minus_i.clone() * partial(g, t);
+        }
+        r += c * g;
+    }
+    Ok(reduce_i(r.expand()))
+}
+
+/// Return the Laplace transform of `f` from `t` to `s`. The symbols
+/// differentiated with respect to `t` in `f` are unknown functions of `t`.
+pub fn laplace(f: Expr, t: &str, s: &str) -> Result<Expr, EvalError> {
+    let mut funcs = Vec::new();
+    unknowns(&f, t, &mut funcs);
+    let f = func::combine_exp(f.expand());
+    let r = laplace_of(f, t, s, &funcs)?;
+    Ok(if funcs.is_empty() { r } else { r.expand() })
+}
+
+/// Return the inverse Laplace transform of `f` from `s` to `t`.
+pub fn invlaplace(f: Expr, s: &str, t: &str) -> Result<Expr, EvalError> {
+    invlaplace_of(f, s, t)
+}
+
+/// Return the Fourier transform `int f(t) exp(-i w t) dt` of `f`.
+pub fn fourier(f: Expr, t: &str, w: &str) -> Result<Expr, EvalError> {
+    let mut funcs = Vec::new();
+    unknowns(&f, t, &mut funcs);
+    let f = func::combine_exp(f.expand());
+    fourier_of(f, t, w, &funcs).map(|e| reduce_i(e.expand()))
+}
+
+/// Return the inverse Fourier transform
+/// `1 / (2 pi) int f(w) exp(i w t) dw` of `f`.
+pub fn invfourier(f: Expr, w: &str, t: &str) -> Result<Expr, EvalError> {
+    invfourier_of(f, w, t)
+}
+
+fn lib_transform(
+    args: Vec<Expr>,
+    transform: fn(Expr, &str, &str) -> Result<Expr, EvalError>,
+) -> Result<Expr, EvalError> {
+    let r = match args.as_slice() {
+        [f, Expr::Sym(x), Expr::Sym(y)] if x != y => {
+            transform(f.clone(), x, y)?
+        }
+        _ => return Err(EvalError::type_mismatch("arguments error")),
+    };
+    // Derivatives are taken with operators which record overflows.
+    if num::take_overflow() {
+        return Err(EvalError::overflow("integer overflow"));
+    }
+    Ok(r)
+}
+
+pub fn lib_laplace(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    lib_transform(args, laplace)
+}
+
+pub fn lib_invlaplace(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    lib_transform(args, invlaplace)
+}
+
+pub fn lib_fourier(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    lib_transform(args, fourier)
+}
+
+pub fn lib_invfourier(args: Vec<Expr>) -> Result<Expr, EvalError> {
+    lib_transform(args, invfourier)
+}
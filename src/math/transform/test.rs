@@ -0,0 +1,120 @@
+use super::super::utl_test::*;
+
+#[test]
+fn test_laplace() {
+    let tests = [
+        (r"\laplace(1, t, s)", "(s ^ -1)"),
+        (r"\laplace(t^2, t, s)", "(2 * (s ^ -3))"),
+        (r"\laplace(t * \exp(2 t), t, s)", "((s + -2) ^ -2)"),
+        (r"\laplace(\sin(3 t), t, s)", "(3 * (((s ^ 2) + 9) ^ -1))"),
+        (
+            r"\laplace(\cos(3 t) * \exp(-t), t, s)",
+            "((s + 1) * ((((s + 1) ^ 2) + 9) ^ -1))",
+        ),
+        (
+            r"\laplace(t * \sin(t), t, s)",
+            "(2 * s * (((s ^ 2) + 1) ^ -2))",
+        ),
+        (r"\laplace(\exp(a t), t, s)", "(((-1 * a) + s) ^ -1)"),
+        (
+            r"\laplace(\diff(y, t), t, s)",
+            "((s * \\laplace((y, t, s))) + (-1 * \\limit((y, t, 0, 1))))",
+        ),
+        (r"\laplace(\ln(t), t, s)", "unsupported transform"),
+        (r"\laplace(t, t)", "arguments error"),
+        (r"\laplace(t, t, t)", "arguments error"),
+        (r"\laplace(t^20, t, s)", "integer overflow"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_invlaplace() {
+    let tests = [
+        (r"\invlaplace(s^-3, s, t)", "(1/2 * (t ^ 2))"),
+        (
+            r"\invlaplace(1/((s-1)(s-2)), s, t)",
+            "((-1 * \\exp(t)) + \\exp((2 * t)))",
+        ),
+        (r"\invlaplace(3/(s^2+9), s, t)", "\\sin((3 * t))"),
+        (
+            r"\invlaplace((s+1)/(s^2+2 s+5), s, t)",
+            "(\\cos((2 * t)) * \\exp((-1 * t)))",
+        ),
+        (
+            r"\invlaplace(1/(s^2 (s+1)), s, t)",
+            "(t + \\exp((-1 * t)) + -1)",
+        ),
+        (r"\invlaplace(s/(s^2+w^2), s, t)", "\\cos((t * w))"),
+        (
+            r"\invlaplace(1/(s^2 - 4), s, t)",
+            "((-1/4 * \\exp((-2 * t))) + (1/4 * \\exp((2 * t))))",
+        ),
+        (r"\invlaplace(s, s, t)", "not a proper rational function"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_laplace_round_trip() {
+    let tests = [
+        r"t * \sin(t)",
+        r"t^2 * \exp(-t)",
+        r"\exp(t) * \cos(2 t)",
+        r"\diff(\diff(y, t), t) + 3 * \diff(y, t) + 2 y",
+    ];
+
+    for f in tests {
+        let input = format!(r"\invlaplace(\laplace({}, t, s), s, t)", f);
+        let expr = parse_expr(&input);
+        assert_eq!(expr, parse_expr(f));
+    }
+}
+
+#[test]
+fn test_fourier() {
+    let tests = [
+        (
+            r"\fourier(\exp(-t^2), t, w)",
+            "((\\pi ^ 1/2) * \\exp((-1/4 * (w ^ 2))))",
+        ),
+        (
+            r"\fourier(t * \exp(-t^2), t, w)",
+            "(-1/2 * \\i * (\\pi ^ 1/2) * w * \\exp((-1/4 * (w ^ 2))))",
+        ),
+        (
+            r"\fourier(\diff(y, t), t, w)",
+            "(\\i * w * \\fourier((y, t, w)))",
+        ),
+        (
+            r"\invfourier(\exp(-w^2), w, t)",
+            "(1/2 * (\\pi ^ -1/2) * \\exp((-1/4 * (t ^ 2))))",
+        ),
+        (r"\fourier(\sin(t), t, w)", "unsupported transform"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_str(input), expected);
+    }
+}
+
+#[test]
+fn test_fourier_round_trip() {
+    let tests = [
+        r"\exp(-t^2)",
+        r"t^2 * \exp(-t^2)",
+        r"\diff(\diff(y, t), t) + y",
+    ];
+
+    for f in tests {
+        let input = format!(r"\invfourier(\fourier({}, t, w), w, t)", f);
+        let expr = parse_expr(&input);
+        assert_eq!(expr, parse_expr(f));
+    }
+}
@@ -1,17 +1,29 @@
 use super::expr::Expr;
+use crate::interpreter::evaluator::{eval_expr, EvalError};
 use crate::interpreter::{
     ast, environment::Environment, lexer::Lexer, parser::Parser,
 };
-use evaluator::eval_expr;
 
-pub fn parse_expr(input: &str) -> Expr {
-    let mut e = Environment::default();
+pub fn try_parse_expr(input: &str) -> Result<Expr, EvalError> {
+    let e = Environment::default();
     let l = Lexer::new(input);
     let mut p = Parser::new(l);
-    let expr = p.parse_stmt();
-    if let ast::Stmt::Expr(expr) = expr {
-        eval_expr(expr, &mut e)
+    let stmt = p.parse_stmt().unwrap();
+    if let ast::StmtKind::Expr(expr) = stmt.kind {
+        eval_expr(expr, &e)
     } else {
         panic!()
     }
 }
+
+pub fn parse_expr(input: &str) -> Expr {
+    try_parse_expr(input).unwrap()
+}
+
+/// Return the value of `input` formatted with `Debug`, or the error message.
+pub fn eval_str(input: &str) -> String {
+    match try_parse_expr(input) {
+        Ok(e) => format!("{:?}", e),
+        Err(e) => e.to_string(),
+    }
+}